@@ -0,0 +1,96 @@
+// Throughput of the face turns and of the ways states are hashed.
+// Run with `cargo bench --bench moves`; criterion compares every run with
+// the previous one, so run it on both commits to compare them.
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rubiks::rubiks::cube::RubiksCube;
+use rubiks::rubiks::cubie::CubieCube;
+use rubiks::rubiks::moves::{algorithm_to_string, generate_scramble};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::hint::black_box;
+
+// Fixed states, the same in every run
+fn scrambled_cubes(count: usize) -> Vec<RubiksCube> {
+    let mut rng = StdRng::seed_from_u64(0);
+    (0..count)
+        .map(|_| {
+            let mut cube = RubiksCube::new();
+            cube.apply_moves(&generate_scramble(&mut rng, 20));
+            cube
+        })
+        .collect()
+}
+
+fn face_turns(c: &mut Criterion) {
+    let mut group = c.benchmark_group("face turns");
+    group.throughput(Throughput::Elements(1));
+    let mut cube = scrambled_cubes(1).remove(0);
+    for (name, move_fn) in RubiksCube::new().all_moves() {
+        group.bench_function(name, |b| b.iter(|| move_fn(black_box(&mut cube))));
+    }
+    group.finish();
+
+    let mut rng = StdRng::seed_from_u64(1);
+    let scramble = generate_scramble(&mut rng, 20);
+    let text = algorithm_to_string(&scramble);
+    let mut group = c.benchmark_group("scramble of 20 moves");
+    group.throughput(Throughput::Elements(20));
+    group.bench_function("apply_moves", |b| {
+        b.iter_batched_ref(
+            RubiksCube::new,
+            |cube| cube.apply_moves(black_box(&scramble)),
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("apply_scramble", |b| {
+        b.iter_batched_ref(
+            RubiksCube::new,
+            |cube| cube.apply_scramble(black_box(&text)),
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+fn hashing(c: &mut Criterion) {
+    let cubes = scrambled_cubes(1000);
+    let mut group = c.benchmark_group("hashing");
+    group.throughput(Throughput::Elements(cubes.len() as u64));
+    // The solvers key their visited sets and the distance table on this
+    group.bench_function("to_string", |b| {
+        b.iter(|| {
+            for cube in &cubes {
+                black_box(cube.to_string());
+            }
+        })
+    });
+    group.bench_function("Hash", |b| {
+        b.iter(|| {
+            for cube in &cubes {
+                let mut hasher = DefaultHasher::new();
+                cube.hash(&mut hasher);
+                black_box(hasher.finish());
+            }
+        })
+    });
+    group.bench_function("HashSet insert", |b| {
+        b.iter(|| {
+            let set: HashSet<&RubiksCube> = cubes.iter().collect();
+            black_box(set.len())
+        })
+    });
+    group.bench_function("CubieCube::from_facelets", |b| {
+        b.iter(|| {
+            for cube in &cubes {
+                black_box(CubieCube::from_facelets(cube).is_ok());
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, face_turns, hashing);
+criterion_main!(benches);
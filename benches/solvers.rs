@@ -0,0 +1,96 @@
+// Time of building the distance table and of every solver on fixed sets of
+// scrambles at several depths. Run with `cargo bench --bench solvers`.
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rubiks::ml::dataset::random_scramble;
+use rubiks::ml::neural_network::NeuralNetwork;
+use rubiks::rubiks::astar::AStarConfig;
+use rubiks::rubiks::cube::RubiksCube;
+use rubiks::rubiks::mcts::MctsConfig;
+use rubiks::rubiks::table::DistanceTable;
+
+// Scrambles per depth; every iteration solves all of them
+const SET_SIZE: usize = 5;
+
+// The same quarter-turn scrambles in every run
+fn scramble_set(depth: usize) -> Vec<RubiksCube> {
+    let mut rng = StdRng::seed_from_u64(depth as u64);
+    (0..SET_SIZE)
+        .map(|_| {
+            let mut cube = RubiksCube::new();
+            cube.apply_scramble(&random_scramble(&mut rng, depth).join(" "));
+            cube
+        })
+        .collect()
+}
+
+fn table_generation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("distance table");
+    group.sample_size(10);
+    for depth in [3, 4, 5] {
+        group.bench_with_input(BenchmarkId::from_parameter(depth), &depth, |b, &depth| {
+            b.iter(|| DistanceTable::new(depth).len())
+        });
+    }
+    group.finish();
+}
+
+fn solvers(c: &mut Criterion) {
+    let table = DistanceTable::new(5);
+    // Untrained but seeded, so that MCTS searches the same tree in every run
+    let network = NeuralNetwork::with_seed(64, 0);
+    let astar = AStarConfig::default();
+    let mcts = MctsConfig {
+        max_simulations: 2000,
+        ..MctsConfig::default()
+    };
+
+    let mut group = c.benchmark_group("solvers");
+    group.sample_size(10);
+    for depth in [2, 4, 6, 8] {
+        let cubes = scramble_set(depth);
+        // BFS visits about 12 times more states per move, so it only gets the shallow sets
+        if depth <= 4 {
+            group.bench_with_input(BenchmarkId::new("bfs", depth), &cubes, |b, cubes| {
+                b.iter(|| {
+                    cubes
+                        .iter()
+                        .filter(|cube| cube.solve_bfs().is_some())
+                        .count()
+                })
+            });
+        }
+        group.bench_with_input(BenchmarkId::new("ida", depth), &cubes, |b, cubes| {
+            b.iter(|| {
+                cubes
+                    .iter()
+                    .filter(|cube| cube.solve_ida(&table, depth).is_some())
+                    .count()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("astar", depth), &cubes, |b, cubes| {
+            b.iter(|| {
+                cubes
+                    .iter()
+                    .filter(|cube| cube.solve_weighted_astar(&table, &astar).is_some())
+                    .count()
+            })
+        });
+        // Deeper, the untrained network just runs into the simulation limit
+        if depth <= 4 {
+            group.bench_with_input(BenchmarkId::new("mcts", depth), &cubes, |b, cubes| {
+                b.iter(|| {
+                    cubes
+                        .iter()
+                        .filter(|cube| cube.solve_mcts(&network, &mcts).is_some())
+                        .count()
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, table_generation, solvers);
+criterion_main!(benches);
@@ -0,0 +1,161 @@
+use clap::Parser;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rubiks::ml::dataset::random_scramble;
+use rubiks::ml::neural_network::NeuralNetwork;
+use rubiks::rubiks::astar::AStarConfig;
+use rubiks::rubiks::cube::RubiksCube;
+use rubiks::rubiks::mcts::MctsConfig;
+use rubiks::rubiks::solver::SearchResult;
+use rubiks::rubiks::table::DistanceTable;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+// Compare solution length and nodes expanded of BFS, IDA*, weighted A* and MCTS
+#[derive(Parser)]
+#[command(name = "compare_solvers")]
+struct Args {
+    /// Weights written by the train binary; without it weighted A* uses the distance table
+    /// and MCTS is skipped
+    #[arg(long)]
+    model: Option<PathBuf>,
+    /// Scramble length
+    #[arg(long, default_value_t = 5)]
+    depth: usize,
+    /// Number of scrambles
+    #[arg(long, default_value_t = 10)]
+    count: usize,
+    #[arg(long, default_value_t = 1.0)]
+    weight: f64,
+    #[arg(long, default_value_t = 100)]
+    batch_size: usize,
+    #[arg(long, default_value_t = 1_000_000)]
+    max_nodes: usize,
+    /// Exploration constant of MCTS
+    #[arg(long, default_value_t = 2.0)]
+    exploration: f64,
+    #[arg(long, default_value_t = 10_000)]
+    max_simulations: usize,
+    /// Depth of the distance table used by IDA*
+    #[arg(long, default_value_t = 5)]
+    table_depth: usize,
+    /// BFS is skipped for deeper scrambles
+    #[arg(long, default_value_t = 5)]
+    bfs_max_depth: usize,
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+}
+
+#[derive(Default)]
+struct Summary {
+    solved: usize,
+    moves: usize,
+    nodes: usize,
+    time: Duration,
+}
+
+impl Summary {
+    fn add(&mut self, result: Option<SearchResult>, time: Duration) {
+        if let Some(result) = result {
+            self.solved += 1;
+            self.moves += result.solution.len();
+            self.nodes += result.nodes_expanded;
+            self.time += time;
+        }
+    }
+
+    fn print(&self, name: &str, nodes: &str, count: usize) {
+        if self.solved == 0 {
+            println!("{:<14} solved 0/{}", name, count);
+            return;
+        }
+        println!(
+            "{:<14} solved {}/{}, mean length {:.2}, mean {} {:.0}, mean time {:?}",
+            name,
+            self.solved,
+            count,
+            self.moves as f64 / self.solved as f64,
+            nodes,
+            self.nodes as f64 / self.solved as f64,
+            self.time / self.solved as u32
+        );
+    }
+}
+
+fn timed<F: FnOnce() -> Option<SearchResult>>(f: F) -> (Option<SearchResult>, Duration) {
+    let start_time = Instant::now();
+    let result = f();
+    (result, start_time.elapsed())
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let network = match &args.model {
+        Some(path) => match NeuralNetwork::load(path) {
+            Ok(network) => Some(network),
+            Err(e) => {
+                eprintln!("error: could not load {}: {}", path.display(), e);
+                return ExitCode::FAILURE;
+            }
+        },
+        None => None,
+    };
+    let table = DistanceTable::new(args.table_depth);
+    let config = AStarConfig {
+        weight: args.weight,
+        batch_size: args.batch_size,
+        max_nodes: args.max_nodes,
+        ..AStarConfig::default()
+    };
+    let mcts_config = MctsConfig {
+        exploration: args.exploration,
+        max_simulations: args.max_simulations,
+        seed: args.seed,
+    };
+
+    let mut rng = StdRng::seed_from_u64(args.seed);
+    let (mut bfs, mut ida, mut astar, mut mcts) = (
+        Summary::default(),
+        Summary::default(),
+        Summary::default(),
+        Summary::default(),
+    );
+    for _ in 0..args.count {
+        let mut cube = RubiksCube::new();
+        cube.apply_scramble(&random_scramble(&mut rng, args.depth).join(" "));
+
+        if args.depth <= args.bfs_max_depth {
+            let (result, time) = timed(|| cube.solve_bfs());
+            bfs.add(result, time);
+        }
+        let (result, time) = timed(|| cube.solve_ida(&table, args.depth));
+        ida.add(result, time);
+        let (result, time) = timed(|| match &network {
+            Some(network) => cube.solve_weighted_astar(network, &config),
+            None => cube.solve_weighted_astar(&table, &config),
+        });
+        astar.add(result, time);
+        if let Some(network) = &network {
+            // Report the tree size in place of the expanded nodes
+            let (result, time) = timed(|| {
+                cube.solve_mcts(network, &mcts_config)
+                    .map(|result| SearchResult {
+                        solution: result.solution,
+                        nodes_expanded: result.tree_size,
+                    })
+            });
+            mcts.add(result, time);
+        }
+    }
+
+    if args.depth <= args.bfs_max_depth {
+        bfs.print("BFS", "nodes expanded", args.count);
+    }
+    ida.print("IDA*", "nodes expanded", args.count);
+    astar.print("Weighted A*", "nodes expanded", args.count);
+    if network.is_some() {
+        mcts.print("MCTS", "tree size", args.count);
+    }
+    ExitCode::SUCCESS
+}
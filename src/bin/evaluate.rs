@@ -0,0 +1,65 @@
+use clap::Parser;
+use rubiks::ml::dataset::read_dataset;
+use rubiks::ml::evaluation::EvaluationReport;
+use rubiks::ml::neural_network::NeuralNetwork;
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+// Evaluate a trained network on a labelled dataset
+#[derive(Parser)]
+#[command(name = "evaluate")]
+struct Args {
+    /// Weights written by the train binary
+    #[arg(long, default_value = "data/model.txt")]
+    model: PathBuf,
+    /// Dataset written by generate_data, ideally with exact labels
+    #[arg(long, default_value = "data/test_data.csv")]
+    dataset: PathBuf,
+    /// Directory for summary.csv, buckets.csv, calibration.csv and confusion.csv
+    #[arg(long)]
+    csv: Option<PathBuf>,
+    /// File for the whole report as JSON
+    #[arg(long)]
+    json: Option<PathBuf>,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let network = match NeuralNetwork::load(&args.model) {
+        Ok(network) => network,
+        Err(e) => {
+            eprintln!("error: could not load {}: {}", args.model.display(), e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let samples = match read_dataset(&args.dataset) {
+        Ok(samples) => samples,
+        Err(e) => {
+            eprintln!("error: could not read {}: {}", args.dataset.display(), e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let predictions: Vec<f64> = samples
+        .iter()
+        .map(|sample| network.cost_to_go(&sample.cube))
+        .collect();
+    let distances: Vec<usize> = samples.iter().map(|sample| sample.distance).collect();
+    let report = EvaluationReport::new(&predictions, &distances);
+    print!("{}", report);
+
+    if let Some(dir) = &args.csv {
+        if let Err(e) = report.write_csv(dir) {
+            eprintln!("error: could not write {}: {}", dir.display(), e);
+            return ExitCode::FAILURE;
+        }
+    }
+    if let Some(path) = &args.json {
+        if let Err(e) = fs::write(path, report.to_json()) {
+            eprintln!("error: could not write {}: {}", path.display(), e);
+            return ExitCode::FAILURE;
+        }
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,60 @@
+use clap::Parser;
+use rubiks::ml::dataset::{generate, write_dataset, DepthDistribution, GeneratorConfig, Label};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Instant;
+
+// Generate labelled datasets of scrambled cubes for training the neural network
+#[derive(Parser)]
+#[command(name = "generate_data")]
+struct Args {
+    /// Number of samples
+    #[arg(long, default_value_t = 1000)]
+    size: usize,
+    /// Scramble lengths: "7", "1-12" (uniform) or "3:1,4:2,5:4" (depth:weight)
+    #[arg(long, default_value = "1-8")]
+    depths: DepthDistribution,
+    /// Label: exact, upper-bound or scramble-depth
+    #[arg(long, default_value = "exact")]
+    label: Label,
+    /// Seed of the random number generator
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    /// Depth of the BFS distance table used for exact labels
+    #[arg(long, default_value_t = 5)]
+    table_depth: usize,
+    /// Output file
+    #[arg(long, short, default_value = "data/training_data.csv")]
+    output: PathBuf,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let config = GeneratorConfig {
+        size: args.size,
+        depths: args.depths,
+        label: args.label,
+        seed: args.seed,
+        table_depth: args.table_depth,
+    };
+
+    let start_time = Instant::now();
+    let samples = match generate(&config) {
+        Ok(samples) => samples,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Err(e) = write_dataset(&args.output, &samples, config.label) {
+        eprintln!("error: could not write {}: {}", args.output.display(), e);
+        return ExitCode::FAILURE;
+    }
+    println!(
+        "Wrote {} samples to {} in {:?}",
+        samples.len(),
+        args.output.display(),
+        start_time.elapsed()
+    );
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,122 @@
+use clap::{Parser, Subcommand};
+use rubiks::ml::neural_network::{self, NeuralNetwork, HIDDEN_NEURONS};
+use rubiks::ml::value_iteration::{train_value_iteration, ValueIterationConfig};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Instant;
+
+// Train the cost-to-go network and store its weights
+#[derive(Parser)]
+#[command(name = "train")]
+struct Args {
+    #[command(subcommand)]
+    mode: Mode,
+    /// File the trained weights are written to
+    #[arg(long, short, global = true, default_value = "data/model.txt")]
+    output: PathBuf,
+}
+
+#[derive(Subcommand)]
+enum Mode {
+    /// Fit labelled datasets written by generate_data
+    Supervised {
+        #[arg(long, default_value = "data/training_data.csv")]
+        train: String,
+        #[arg(long, default_value = "data/test_data.csv")]
+        test: String,
+    },
+    /// DeepCubeA-style approximate value iteration on random walks from solved
+    ValueIteration {
+        #[arg(long, default_value_t = 1000)]
+        iterations: usize,
+        #[arg(long, default_value_t = 500, value_parser = at_least_one)]
+        batch_size: usize,
+        /// Longest random walk from solved
+        #[arg(long, default_value_t = 20, value_parser = at_least_one)]
+        max_depth: usize,
+        /// Training passes over each batch
+        #[arg(long, default_value_t = 1)]
+        epochs: usize,
+        /// Iterations between target network updates
+        #[arg(long, default_value_t = 20, value_parser = at_least_one)]
+        update_interval: usize,
+        /// Also train the policy head used by the MCTS solver
+        #[arg(long)]
+        policy: bool,
+        #[arg(long, default_value_t = HIDDEN_NEURONS)]
+        hidden_neurons: usize,
+        #[arg(long, default_value_t = 0.001)]
+        learning_rate: f64,
+        /// Weight noise added during training
+        #[arg(long, default_value_t = 0.0)]
+        noise: f64,
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+    },
+}
+
+fn at_least_one(s: &str) -> Result<usize, String> {
+    match s.parse() {
+        Ok(0) => Err("must be at least 1".to_string()),
+        Ok(n) => Ok(n),
+        Err(e) => Err(format!("{}", e)),
+    }
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let network = match args.mode {
+        Mode::Supervised { train, test } => neural_network::run(&train, &test),
+        Mode::ValueIteration {
+            iterations,
+            batch_size,
+            max_depth,
+            epochs,
+            update_interval,
+            policy,
+            hidden_neurons,
+            learning_rate,
+            noise,
+            seed,
+        } => {
+            let mut network = NeuralNetwork::with_seed(hidden_neurons, seed);
+            network.learning_rate = learning_rate;
+            network.noise = noise;
+            let config = ValueIterationConfig {
+                iterations,
+                batch_size,
+                max_depth,
+                epochs,
+                update_interval,
+                train_policy: policy,
+                seed,
+            };
+
+            let start_time = Instant::now();
+            let trained = train_value_iteration(&mut network, &config, |stats| {
+                if stats.target_updated {
+                    println!(
+                        "Iteration {}: loss {:.3}, mean target {:.2}",
+                        stats.iteration, stats.loss, stats.mean_target
+                    );
+                }
+            });
+            if let Err(e) = trained {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+            println!("Elapsed time: {:?}", start_time.elapsed());
+            network
+        }
+    };
+
+    if let Some(parent) = args.output.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Err(e) = network.save(&args.output) {
+        eprintln!("error: could not write {}: {}", args.output.display(), e);
+        return ExitCode::FAILURE;
+    }
+    println!("Saved network to {}", args.output.display());
+    ExitCode::SUCCESS
+}
@@ -1,2 +1,4 @@
+pub mod ml;
+pub mod render;
 pub mod rubiks;
 pub mod ui;
@@ -1,24 +1,471 @@
+use clap::{Parser, Subcommand};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rubiks::ml::neural_network::NeuralNetwork;
+use rubiks::render::{pll_arrows, render, RenderOptions, View};
+use rubiks::rubiks::astar::AStarConfig;
+use rubiks::rubiks::batch::{parse_scrambles, solve_batch, BatchSummary, Solver, SolverConfig};
 use rubiks::rubiks::cube::RubiksCube;
+use rubiks::rubiks::kociemba::KociembaTables;
+use rubiks::rubiks::mcts::MctsConfig;
+use rubiks::rubiks::metric::Metric;
+use rubiks::rubiks::moves::{algorithm_to_string, generate_scramble, parse_algorithm, Move};
+use rubiks::rubiks::table::DistanceTable;
+use rubiks::ui::scheme::{load_schemes, ColorScheme};
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::str::FromStr;
 use std::time::Instant;
 
-fn main() {
-    // Create new instance of the Cube
-    let mut cube = RubiksCube::new();
-    // Define the scramble in the standard notation
-    let scramble = "L R U D";
-    // Scramble the Cube
-    cube.apply_scramble(scramble);
+// Exit codes: 0 on success, 1 if the answer is negative (no solution found,
+// the solution doesn't solve) or a file can't be read or written, 2 if the
+// input is invalid (the same code clap uses for usage errors)
+const NEGATIVE: u8 = 1;
+const INVALID: u8 = 2;
+
+/// Solve, scramble, verify and draw cubes; opens the visualizer without a subcommand
+#[derive(Parser)]
+#[command(name = "rubiks")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve a scramble
+    Solve(SolveArgs),
+    /// Print random competition-style scrambles
+    Scramble(ScrambleArgs),
+    /// Solve every scramble of a file in parallel
+    Batch(BatchArgs),
+    /// Check that a solution solves a scramble
+    Verify(VerifyArgs),
+    /// Draw a scrambled cube as SVG or PNG
+    Render(RenderArgs),
+    /// Open the visualizer
+    Gui(GuiArgs),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Format {
+    Text,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("unknown format {}, expected text or json", s)),
+        }
+    }
+}
+
+#[derive(clap::Args)]
+struct SolverArgs {
+    /// bfs, ida (optimal, with the distance table as heuristic), astar, mcts or kociemba
+    /// (two-phase, short but not optimal solutions of any scramble)
+    #[arg(long, default_value = "ida")]
+    solver: Solver,
+    /// qtm, htm, stm or atm: the moves bfs, ida and astar search with and count;
+    /// mcts only makes quarter turns, kociemba makes face turns and counts them in the metric
+    #[arg(long, default_value = "qtm")]
+    metric: Metric,
+    /// Weights written by the train binary; needed by mcts, astar falls back to the
+    /// distance table without it
+    #[arg(long)]
+    model: Option<PathBuf>,
+    /// IDA* gives up if there is no solution of at most this many moves
+    #[arg(long, default_value_t = 12)]
+    max_depth: usize,
+    /// kociemba gives up if it finds no solution of at most this many face turns
+    #[arg(long, default_value_t = 30)]
+    max_length: usize,
+    /// Depth of the distance table used by ida and astar
+    #[arg(long, default_value_t = 5)]
+    table_depth: usize,
+    /// Weight of the heuristic in astar
+    #[arg(long, default_value_t = 1.0)]
+    weight: f64,
+    /// astar gives up after expanding this many nodes
+    #[arg(long, default_value_t = 1_000_000)]
+    max_nodes: usize,
+    #[arg(long, default_value_t = 10_000)]
+    max_simulations: usize,
+}
+
+#[derive(clap::Args)]
+struct SolveArgs {
+    /// Moves in standard notation, e.g. "R U R' U'"
+    scramble: String,
+    #[command(flatten)]
+    solver: SolverArgs,
+    /// text or json
+    #[arg(long, default_value = "text")]
+    format: Format,
+}
+
+#[derive(clap::Args)]
+struct BatchArgs {
+    /// File with one scramble per line or a JSON array of scrambles
+    input: PathBuf,
+    /// CSV file for the results; results already in it are kept and not solved again
+    #[arg(long, short)]
+    output: PathBuf,
+    #[command(flatten)]
+    solver: SolverArgs,
+    /// Number of worker threads, all cores if not given
+    #[arg(long)]
+    threads: Option<usize>,
+    /// Format of the summary: text or json
+    #[arg(long, default_value = "text")]
+    format: Format,
+}
+
+#[derive(clap::Args)]
+struct ScrambleArgs {
+    #[arg(long, default_value_t = 1)]
+    count: usize,
+    /// Moves per scramble
+    #[arg(long, default_value_t = 20)]
+    length: usize,
+    /// Seed of the random number generator; random if not given
+    #[arg(long)]
+    seed: Option<u64>,
+    /// text (one scramble per line) or json
+    #[arg(long, default_value = "text")]
+    format: Format,
+}
+
+#[derive(clap::Args)]
+struct VerifyArgs {
+    scramble: String,
+    solution: String,
+    /// text or json
+    #[arg(long, default_value = "text")]
+    format: Format,
+}
+
+#[derive(clap::Args)]
+struct RenderArgs {
+    /// Moves applied to the solved cube before drawing
+    #[arg(default_value = "")]
+    scramble: String,
+    /// Output file, .svg or .png
+    #[arg(long, short)]
+    output: PathBuf,
+    /// net, isometric or last-layer
+    #[arg(long, default_value = "net")]
+    view: View,
+    /// Name of a built-in scheme or of one in --scheme-file
+    #[arg(long, default_value = "Standard")]
+    scheme: String,
+    /// File with colour schemes, in the format of color_schemes.txt
+    #[arg(long)]
+    scheme_file: Option<PathBuf>,
+    /// Write letters on the stickers
+    #[arg(long)]
+    letters: bool,
+    /// Draw arrows for the permutation of the last layer (last-layer view)
+    #[arg(long)]
+    arrows: bool,
+    /// Size of a sticker in pixels
+    #[arg(long, default_value_t = 40.0)]
+    sticker_size: f32,
+}
+
+#[derive(clap::Args)]
+struct GuiArgs {
+    /// Moves applied to the solved cube before opening the window
+    #[arg(default_value = "")]
+    scramble: String,
+}
+
+fn parse(moves: &str) -> Result<Vec<Move>, ExitCode> {
+    parse_algorithm(moves).map_err(|e| {
+        eprintln!("error: {}", e);
+        ExitCode::from(INVALID)
+    })
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+// Length of a solution in every metric, as a JSON object
+fn lengths_json(solution: &[Move]) -> String {
+    let lengths: Vec<String> = Metric::ALL
+        .iter()
+        .map(|metric| format!("\"{}\":{}", metric.name(), metric.length(solution)))
+        .collect();
+    format!("{{{}}}", lengths.join(","))
+}
+
+fn load_model(args: &SolverArgs) -> Result<Option<NeuralNetwork>, ExitCode> {
+    if args.solver == Solver::Mcts && args.metric != Metric::Qtm {
+        eprintln!("error: the mcts solver only makes quarter turns, it needs --metric qtm");
+        return Err(ExitCode::from(INVALID));
+    }
+    let Some(path) = &args.model else {
+        if args.solver == Solver::Mcts {
+            eprintln!("error: the mcts solver needs a --model");
+            return Err(ExitCode::from(INVALID));
+        }
+        return Ok(None);
+    };
+    NeuralNetwork::load(path).map(Some).map_err(|e| {
+        eprintln!("error: could not load {}: {}", path.display(), e);
+        ExitCode::from(NEGATIVE)
+    })
+}
+
+fn distance_table(args: &SolverArgs) -> DistanceTable {
+    match args.solver {
+        Solver::Ida | Solver::AStar => DistanceTable::with_metric(args.table_depth, args.metric),
+        // Only tells BFS the metric
+        Solver::Bfs | Solver::Mcts | Solver::Kociemba => DistanceTable::with_metric(0, args.metric),
+    }
+}
+
+fn kociemba_tables(args: &SolverArgs) -> Option<KociembaTables> {
+    (args.solver == Solver::Kociemba).then(KociembaTables::new)
+}
+
+fn solver_config<'a>(
+    args: &SolverArgs,
+    table: &'a DistanceTable,
+    model: Option<&'a NeuralNetwork>,
+    kociemba: Option<&'a KociembaTables>,
+) -> SolverConfig<'a, NeuralNetwork> {
+    SolverConfig {
+        solver: args.solver,
+        table,
+        model,
+        kociemba,
+        max_depth: args.max_depth,
+        max_length: args.max_length,
+        astar: AStarConfig {
+            weight: args.weight,
+            max_nodes: args.max_nodes,
+            ..AStarConfig::default()
+        },
+        mcts: MctsConfig {
+            max_simulations: args.max_simulations,
+            ..MctsConfig::default()
+        },
+    }
+}
+
+fn solve(args: SolveArgs) -> Result<(), ExitCode> {
+    let scramble = parse(&args.scramble)?;
+    let cube = RubiksCube::from_moves(&scramble);
+    let model = load_model(&args.solver)?;
+    let table = distance_table(&args.solver);
+    let kociemba = kociemba_tables(&args.solver);
+    let config = solver_config(&args.solver, &table, model.as_ref(), kociemba.as_ref());
 
-    // Solve the cube using BFS
     let start_time = Instant::now();
-    if let Some(solution) = cube.solve() {
-        println!("Solution found in {} moves: {:?}", solution.len(), solution);
+    let result = config.solve(&cube);
+    let time = start_time.elapsed();
+
+    match args.format {
+        Format::Text => match &result {
+            Some(result) => println!("{}", result.solution.join(" ")),
+            None => eprintln!("No solution found."),
+        },
+        Format::Json => {
+            let (solution, length, lengths, nodes) = match &result {
+                Some(result) => {
+                    let moves = parse_algorithm(&result.solution.join(" "))
+                        .expect("solvers write standard notation");
+                    (
+                        json_string(&result.solution.join(" ")),
+                        args.solver.metric.length(&moves).to_string(),
+                        lengths_json(&moves),
+                        result.nodes_expanded,
+                    )
+                }
+                None => (
+                    "null".to_string(),
+                    "null".to_string(),
+                    "null".to_string(),
+                    0,
+                ),
+            };
+            println!(
+                "{{\"scramble\":{},\"solver\":\"{}\",\"solved\":{},\"solution\":{},\"length\":{},\"metric\":\"{}\",\"lengths\":{},\"nodes_expanded\":{},\"time_ms\":{:.3}}}",
+                json_string(&algorithm_to_string(&scramble)),
+                args.solver.solver.name(),
+                result.is_some(),
+                solution,
+                length,
+                args.solver.metric.name(),
+                lengths,
+                nodes,
+                time.as_secs_f64() * 1000.0
+            );
+        }
+    }
+    match result {
+        Some(_) => Ok(()),
+        None => Err(ExitCode::from(NEGATIVE)),
+    }
+}
+
+fn batch(args: BatchArgs) -> Result<(), ExitCode> {
+    let text = fs::read_to_string(&args.input).map_err(|e| {
+        eprintln!("error: could not read {}: {}", args.input.display(), e);
+        ExitCode::from(NEGATIVE)
+    })?;
+    let scrambles = parse_scrambles(&text).map_err(|e| {
+        eprintln!("error: {}: {}", args.input.display(), e);
+        ExitCode::from(INVALID)
+    })?;
+    let model = load_model(&args.solver)?;
+    let table = distance_table(&args.solver);
+    let kociemba = kociemba_tables(&args.solver);
+    let config = solver_config(&args.solver, &table, model.as_ref(), kociemba.as_ref());
+
+    let mut pool = rayon::ThreadPoolBuilder::new();
+    if let Some(threads) = args.threads {
+        pool = pool.num_threads(threads);
+    }
+    let pool = pool.build().map_err(|e| {
+        eprintln!("error: {}", e);
+        ExitCode::from(NEGATIVE)
+    })?;
+    let results = pool
+        .install(|| solve_batch(&scrambles, &config, &args.output))
+        .map_err(|e| {
+            eprintln!("error: {}: {}", args.output.display(), e);
+            ExitCode::from(NEGATIVE)
+        })?;
+
+    let summary = BatchSummary::new(&results);
+    match args.format {
+        Format::Text => print!("{}", summary),
+        Format::Json => println!("{}", summary.to_json()),
+    }
+    if summary.solved == summary.count {
+        Ok(())
+    } else {
+        Err(ExitCode::from(NEGATIVE))
+    }
+}
+
+fn scramble(args: ScrambleArgs) -> Result<(), ExitCode> {
+    let mut rng = match args.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let scrambles: Vec<String> = (0..args.count)
+        .map(|_| algorithm_to_string(&generate_scramble(&mut rng, args.length)))
+        .collect();
+    match args.format {
+        Format::Text => {
+            for scramble in &scrambles {
+                println!("{}", scramble);
+            }
+        }
+        Format::Json => {
+            let scrambles: Vec<String> = scrambles.iter().map(|s| json_string(s)).collect();
+            println!("[{}]", scrambles.join(","));
+        }
+    }
+    Ok(())
+}
+
+fn verify(args: VerifyArgs) -> Result<(), ExitCode> {
+    let scramble = parse(&args.scramble)?;
+    let solution = parse(&args.solution)?;
+    let mut cube = RubiksCube::from_moves(&scramble);
+    cube.apply_moves(&solution);
+    let solved = cube.is_solved();
+    match args.format {
+        Format::Text => println!("{}", if solved { "solved" } else { "not solved" }),
+        Format::Json => println!(
+            "{{\"solved\":{},\"length\":{},\"lengths\":{}}}",
+            solved,
+            solution.len(),
+            lengths_json(&solution)
+        ),
+    }
+    if solved {
+        Ok(())
     } else {
-        println!("No solution found.");
+        Err(ExitCode::from(NEGATIVE))
+    }
+}
+
+fn render_cube(args: RenderArgs) -> Result<(), ExitCode> {
+    let cube = RubiksCube::from_moves(&parse(&args.scramble)?);
+    let mut schemes = ColorScheme::builtin();
+    if let Some(path) = &args.scheme_file {
+        schemes.extend(load_schemes(path).map_err(|e| {
+            eprintln!("error: {}", e);
+            ExitCode::from(NEGATIVE)
+        })?);
     }
-    let elapsed_time = start_time.elapsed();
-    println!("Elapsed time: {:?}", elapsed_time);
+    let Some(mut scheme) = schemes
+        .into_iter()
+        .find(|scheme| scheme.name.eq_ignore_ascii_case(&args.scheme))
+    else {
+        eprintln!("error: unknown colour scheme {}", args.scheme);
+        return Err(ExitCode::from(INVALID));
+    };
+    scheme.show_letters |= args.letters;
+
+    let options = RenderOptions {
+        view: args.view,
+        scheme,
+        sticker_size: args.sticker_size,
+        arrows: if args.arrows {
+            pll_arrows(&cube)
+        } else {
+            Vec::new()
+        },
+    };
+    render(&cube, &options).save(&args.output).map_err(|e| {
+        eprintln!("error: {}", e);
+        ExitCode::from(NEGATIVE)
+    })
+}
 
-    // Visualize scrambled cube
-    cube.clone().visualize();
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Some(Command::Solve(args)) => solve(args),
+        Some(Command::Scramble(args)) => scramble(args),
+        Some(Command::Batch(args)) => batch(args),
+        Some(Command::Verify(args)) => verify(args),
+        Some(Command::Render(args)) => render_cube(args),
+        Some(Command::Gui(args)) => {
+            // A scramble given here wins over the cube of the saved session
+            parse(&args.scramble)
+                .map(|moves| RubiksCube::from_moves(&moves).visualize_with(!moves.is_empty()))
+        }
+        None => {
+            RubiksCube::new().visualize();
+            Ok(())
+        }
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(code) => code,
+    }
 }
@@ -0,0 +1,247 @@
+//! Labelled datasets of scrambled cubes for training the neural network.
+//!
+//! Datasets are stored as plain text, one sample per line:
+//!
+//! ```text
+//! # rubiks dataset v1
+//! # label: exact
+//! state,distance,scramble
+//! WWWWWWOOORRRYYYYYYGGGGGGGGGBBBBBBBBBWRRWRRWRROOYOOYOOY,1,F
+//! ```
+//!
+//! Lines starting with `#` are comments. `state` is the 54 character string
+//! produced by `RubiksCube::to_string` (faces in the order U D F B R L, stickers
+//! row by row), `distance` is the label in quarter turns and `scramble` is the
+//! move sequence that produced the state from solved.
+use crate::rubiks::cube::RubiksCube;
+use crate::rubiks::table::DistanceTable;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::fmt;
+use std::fs;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+const HEADER: &str = "state,distance,scramble";
+
+// How the distance of a generated state is determined
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Label {
+    // Optimal distance from the distance table, or IDA* beyond it
+    Exact,
+    // Exact inside the distance table, otherwise the scramble length
+    UpperBound,
+    // Number of moves in the scramble
+    ScrambleDepth,
+}
+
+impl fmt::Display for Label {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Label::Exact => write!(f, "exact"),
+            Label::UpperBound => write!(f, "upper-bound"),
+            Label::ScrambleDepth => write!(f, "scramble-depth"),
+        }
+    }
+}
+
+impl FromStr for Label {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "exact" => Ok(Label::Exact),
+            "upper-bound" => Ok(Label::UpperBound),
+            "scramble-depth" => Ok(Label::ScrambleDepth),
+            _ => Err(format!(
+                "unknown label '{}', expected exact, upper-bound or scramble-depth",
+                s
+            )),
+        }
+    }
+}
+
+// Distribution of the scramble lengths
+#[derive(Debug, Clone, PartialEq)]
+pub enum DepthDistribution {
+    // Every depth in min..=max equally likely
+    Uniform { min: usize, max: usize },
+    // (depth, relative weight) pairs
+    Weighted(Vec<(usize, f64)>),
+}
+
+// Accepts "7" (fixed depth), "1-12" (uniform) or "3:1,4:2,5:4" (weighted)
+impl FromStr for DepthDistribution {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_depth = |d: &str| {
+            d.trim()
+                .parse::<usize>()
+                .map_err(|_| format!("invalid depth '{}'", d))
+        };
+
+        if s.contains(':') {
+            let mut weights = Vec::new();
+            for entry in s.split(',') {
+                let (depth, weight) = entry
+                    .split_once(':')
+                    .ok_or_else(|| format!("expected depth:weight, got '{}'", entry))?;
+                let weight = weight
+                    .trim()
+                    .parse::<f64>()
+                    .map_err(|_| format!("invalid weight '{}'", weight))?;
+                weights.push((parse_depth(depth)?, weight));
+            }
+            Ok(DepthDistribution::Weighted(weights))
+        } else if let Some((min, max)) = s.split_once('-') {
+            let (min, max) = (parse_depth(min)?, parse_depth(max)?);
+            if min > max {
+                return Err(format!("empty depth range '{}'", s));
+            }
+            Ok(DepthDistribution::Uniform { min, max })
+        } else {
+            let depth = parse_depth(s)?;
+            Ok(DepthDistribution::Uniform {
+                min: depth,
+                max: depth,
+            })
+        }
+    }
+}
+
+pub struct GeneratorConfig {
+    pub size: usize,
+    pub depths: DepthDistribution,
+    pub label: Label,
+    pub seed: u64,
+    // Depth of the BFS distance table used for exact labels
+    pub table_depth: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    pub cube: RubiksCube,
+    pub distance: usize,
+    pub scramble: Vec<String>,
+}
+
+// Random sequence of quarter turns that never undoes the previous move
+pub fn random_scramble<R: Rng>(rng: &mut R, depth: usize) -> Vec<&'static str> {
+    let moves = RubiksCube::new().all_moves();
+    let mut scramble: Vec<usize> = Vec::with_capacity(depth);
+    while scramble.len() < depth {
+        let i = rng.gen_range(0..moves.len());
+        // Moves come in (X, X') pairs
+        if scramble.last() == Some(&(i ^ 1)) {
+            continue;
+        }
+        scramble.push(i);
+    }
+    scramble.into_iter().map(|i| moves[i].0).collect()
+}
+
+pub fn generate(config: &GeneratorConfig) -> Result<Vec<Sample>, String> {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let depths = match &config.depths {
+        DepthDistribution::Uniform { min, max } => (*min..=*max).map(|d| (d, 1.0)).collect(),
+        DepthDistribution::Weighted(weights) => weights.clone(),
+    };
+    let weights = WeightedIndex::new(depths.iter().map(|&(_, w)| w))
+        .map_err(|e| format!("invalid depth weights: {}", e))?;
+    let table = match config.label {
+        Label::ScrambleDepth => None,
+        _ => Some(DistanceTable::new(config.table_depth)),
+    };
+
+    let mut samples = Vec::with_capacity(config.size);
+    for _ in 0..config.size {
+        let depth = depths[weights.sample(&mut rng)].0;
+        let scramble = random_scramble(&mut rng, depth);
+        let mut cube = RubiksCube::new();
+        cube.apply_scramble(&scramble.join(" "));
+
+        let distance = match (&table, config.label) {
+            (Some(table), Label::Exact) => table.get(&cube).unwrap_or_else(|| {
+                // The scramble itself bounds the search depth
+                cube.solve_ida(table, depth)
+                    .expect("the inverse scramble is a solution")
+                    .solution
+                    .len()
+            }),
+            (Some(table), Label::UpperBound) => table.get(&cube).unwrap_or(depth),
+            _ => depth,
+        };
+
+        samples.push(Sample {
+            cube,
+            distance,
+            scramble: scramble.into_iter().map(String::from).collect(),
+        });
+    }
+    Ok(samples)
+}
+
+pub fn write_dataset<P: AsRef<Path>>(path: P, samples: &[Sample], label: Label) -> io::Result<()> {
+    if let Some(parent) = path.as_ref().parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut writer = BufWriter::new(fs::File::create(path)?);
+    writeln!(writer, "# rubiks dataset v1")?;
+    writeln!(writer, "# label: {}", label)?;
+    writeln!(writer, "{}", HEADER)?;
+    for sample in samples {
+        writeln!(
+            writer,
+            "{},{},{}",
+            sample.cube.to_string(),
+            sample.distance,
+            sample.scramble.join(" ")
+        )?;
+    }
+    writer.flush()
+}
+
+pub fn read_dataset<P: AsRef<Path>>(path: P) -> io::Result<Vec<Sample>> {
+    let reader = BufReader::new(fs::File::open(path)?);
+    let invalid = |line_nr: usize, msg: &str| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("line {}: {}", line_nr + 1, msg),
+        )
+    };
+
+    let mut samples = Vec::new();
+    for (line_nr, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line == HEADER {
+            continue;
+        }
+
+        let mut fields = line.splitn(3, ',');
+        let cube = fields
+            .next()
+            .and_then(|state| RubiksCube::from_state_string(state).ok())
+            .ok_or_else(|| invalid(line_nr, "invalid state"))?;
+        let distance = fields
+            .next()
+            .and_then(|d| d.trim().parse().ok())
+            .ok_or_else(|| invalid(line_nr, "invalid distance"))?;
+        let scramble = fields
+            .next()
+            .unwrap_or("")
+            .split_whitespace()
+            .map(String::from)
+            .collect();
+
+        samples.push(Sample {
+            cube,
+            distance,
+            scramble,
+        });
+    }
+    Ok(samples)
+}
@@ -0,0 +1,272 @@
+//! Evaluation of cost-to-go estimates against known distances.
+//!
+//! Besides the overall error this reports how the error depends on the true
+//! distance, how often the estimate overestimates (which makes it inadmissible
+//! for optimal search), how well predictions are calibrated and a confusion
+//! matrix of rounded predictions against true distances.
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+// Error statistics of all samples with the same true distance
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistanceBucket {
+    pub distance: usize,
+    pub count: usize,
+    pub mean_prediction: f64,
+    pub mae: f64,
+    pub rmse: f64,
+    // Fraction of predictions above the true distance
+    pub overestimates: f64,
+}
+
+// Mean true distance of all samples whose rounded prediction is `predicted`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalibrationBin {
+    pub predicted: usize,
+    pub count: usize,
+    pub mean_distance: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvaluationReport {
+    pub count: usize,
+    pub mae: f64,
+    pub rmse: f64,
+    pub overestimates: f64,
+    // Fraction of rounded predictions equal to the true distance
+    pub exact: f64,
+    pub buckets: Vec<DistanceBucket>,
+    pub calibration: Vec<CalibrationBin>,
+    // confusion[true distance][rounded prediction]
+    pub confusion: Vec<Vec<usize>>,
+}
+
+// JSON has no NaN or infinity, e.g. the means of an empty bucket or of a
+// diverged network
+fn json_number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+fn rounded(prediction: f64) -> usize {
+    prediction.round().max(0.0) as usize
+}
+
+impl EvaluationReport {
+    // Predictions are in moves, i.e. already scaled back by SCALE
+    pub fn new(predictions: &[f64], distances: &[usize]) -> Self {
+        assert_eq!(predictions.len(), distances.len());
+        let count = predictions.len();
+        let pairs: Vec<(f64, usize)> = predictions
+            .iter()
+            .cloned()
+            .zip(distances.iter().cloned())
+            .collect();
+        let mean = |values: &mut dyn Iterator<Item = f64>, n: usize| {
+            if n == 0 {
+                0.0
+            } else {
+                values.sum::<f64>() / n as f64
+            }
+        };
+
+        let max_distance = distances.iter().cloned().max().unwrap_or(0);
+        let max_prediction = predictions.iter().cloned().map(rounded).max().unwrap_or(0);
+
+        let buckets = (0..=max_distance)
+            .filter_map(|distance| {
+                let bucket: Vec<f64> = pairs
+                    .iter()
+                    .filter(|&&(_, d)| d == distance)
+                    .map(|&(p, _)| p)
+                    .collect();
+                if bucket.is_empty() {
+                    return None;
+                }
+                let n = bucket.len();
+                let d = distance as f64;
+                Some(DistanceBucket {
+                    distance,
+                    count: n,
+                    mean_prediction: mean(&mut bucket.iter().cloned(), n),
+                    mae: mean(&mut bucket.iter().map(|p| (p - d).abs()), n),
+                    rmse: mean(&mut bucket.iter().map(|p| (p - d).powi(2)), n).sqrt(),
+                    overestimates: bucket.iter().filter(|&&p| p > d).count() as f64 / n as f64,
+                })
+            })
+            .collect();
+
+        let calibration = (0..=max_prediction)
+            .filter_map(|predicted| {
+                let bin: Vec<usize> = pairs
+                    .iter()
+                    .filter(|&&(p, _)| rounded(p) == predicted)
+                    .map(|&(_, d)| d)
+                    .collect();
+                if bin.is_empty() {
+                    return None;
+                }
+                Some(CalibrationBin {
+                    predicted,
+                    count: bin.len(),
+                    mean_distance: mean(&mut bin.iter().map(|&d| d as f64), bin.len()),
+                })
+            })
+            .collect();
+
+        let mut confusion = vec![vec![0; max_prediction + 1]; max_distance + 1];
+        for &(p, d) in &pairs {
+            confusion[d][rounded(p)] += 1;
+        }
+
+        EvaluationReport {
+            count,
+            mae: mean(&mut pairs.iter().map(|&(p, d)| (p - d as f64).abs()), count),
+            rmse: mean(
+                &mut pairs.iter().map(|&(p, d)| (p - d as f64).powi(2)),
+                count,
+            )
+            .sqrt(),
+            overestimates: mean(
+                &mut pairs.iter().map(|&(p, d)| (p > d as f64) as u8 as f64),
+                count,
+            ),
+            exact: mean(
+                &mut pairs.iter().map(|&(p, d)| (rounded(p) == d) as u8 as f64),
+                count,
+            ),
+            buckets,
+            calibration,
+            confusion,
+        }
+    }
+
+    // Writes summary.csv, buckets.csv, calibration.csv and confusion.csv into `dir`
+    pub fn write_csv<P: AsRef<Path>>(&self, dir: P) -> io::Result<()> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+
+        let mut file = fs::File::create(dir.join("summary.csv"))?;
+        writeln!(file, "count,mae,rmse,overestimates,exact")?;
+        writeln!(
+            file,
+            "{},{},{},{},{}",
+            self.count, self.mae, self.rmse, self.overestimates, self.exact
+        )?;
+
+        let mut file = fs::File::create(dir.join("buckets.csv"))?;
+        writeln!(
+            file,
+            "distance,count,mean_prediction,mae,rmse,overestimates"
+        )?;
+        for b in &self.buckets {
+            writeln!(
+                file,
+                "{},{},{},{},{},{}",
+                b.distance, b.count, b.mean_prediction, b.mae, b.rmse, b.overestimates
+            )?;
+        }
+
+        let mut file = fs::File::create(dir.join("calibration.csv"))?;
+        writeln!(file, "predicted,count,mean_distance")?;
+        for c in &self.calibration {
+            writeln!(file, "{},{},{}", c.predicted, c.count, c.mean_distance)?;
+        }
+
+        // One row per true distance, one column per rounded prediction
+        let mut file = fs::File::create(dir.join("confusion.csv"))?;
+        let columns: Vec<String> = (0..self.confusion.first().map_or(0, |row| row.len()))
+            .map(|p| p.to_string())
+            .collect();
+        writeln!(file, "distance,{}", columns.join(","))?;
+        for (d, row) in self.confusion.iter().enumerate() {
+            let row: Vec<String> = row.iter().map(|c| c.to_string()).collect();
+            writeln!(file, "{},{}", d, row.join(","))?;
+        }
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> String {
+        let buckets: Vec<String> = self
+            .buckets
+            .iter()
+            .map(|b| {
+                format!(
+                    "{{\"distance\":{},\"count\":{},\"mean_prediction\":{},\"mae\":{},\"rmse\":{},\"overestimates\":{}}}",
+                    b.distance,
+                    b.count,
+                    json_number(b.mean_prediction),
+                    json_number(b.mae),
+                    json_number(b.rmse),
+                    json_number(b.overestimates)
+                )
+            })
+            .collect();
+        let calibration: Vec<String> = self
+            .calibration
+            .iter()
+            .map(|c| {
+                format!(
+                    "{{\"predicted\":{},\"count\":{},\"mean_distance\":{}}}",
+                    c.predicted,
+                    c.count,
+                    json_number(c.mean_distance)
+                )
+            })
+            .collect();
+        let confusion: Vec<String> = self
+            .confusion
+            .iter()
+            .map(|row| {
+                let row: Vec<String> = row.iter().map(|c| c.to_string()).collect();
+                format!("[{}]", row.join(","))
+            })
+            .collect();
+        format!(
+            "{{\"count\":{},\"mae\":{},\"rmse\":{},\"overestimates\":{},\"exact\":{},\"buckets\":[{}],\"calibration\":[{}],\"confusion\":[{}]}}",
+            self.count,
+            json_number(self.mae),
+            json_number(self.rmse),
+            json_number(self.overestimates),
+            json_number(self.exact),
+            buckets.join(","),
+            calibration.join(","),
+            confusion.join(",")
+        )
+    }
+}
+
+impl std::fmt::Display for EvaluationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} samples: MAE {:.3}, RMSE {:.3}, overestimates {:.1}%, exact {:.1}%",
+            self.count,
+            self.mae,
+            self.rmse,
+            100.0 * self.overestimates,
+            100.0 * self.exact
+        )?;
+        writeln!(
+            f,
+            "distance  count  mean prediction    MAE   RMSE  overestimates"
+        )?;
+        for b in &self.buckets {
+            writeln!(
+                f,
+                "{:>8}  {:>5}  {:>15.2}  {:>5.2}  {:>5.2}  {:>12.1}%",
+                b.distance,
+                b.count,
+                b.mean_prediction,
+                b.mae,
+                b.rmse,
+                100.0 * b.overestimates
+            )?;
+        }
+        Ok(())
+    }
+}
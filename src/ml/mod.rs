@@ -0,0 +1,4 @@
+pub mod dataset;
+pub mod evaluation;
+pub mod neural_network;
+pub mod value_iteration;
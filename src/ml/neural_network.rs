@@ -0,0 +1,450 @@
+use super::dataset::read_dataset;
+use super::evaluation::EvaluationReport;
+use crate::rubiks::astar::CostToGo;
+use crate::rubiks::cube::RubiksCube;
+use crate::rubiks::mcts::Policy;
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::Instant;
+
+// collection of activation functions
+fn sigmoid(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+fn sigmoid_derivative(x: f64) -> f64 {
+    x * (1.0 - x)
+}
+
+fn relu(x: f64) -> f64 {
+    if x > 0.0 {
+        x
+    } else {
+        0.0
+    }
+}
+
+fn relu_derivative(x: f64) -> f64 {
+    if x > 0.0 {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+//Some constants
+pub const SCALE: f64 = 80.0; //For rescaling the values
+pub const EPISODES: usize = 10; //How many episodes should the NN learn
+pub const HIDDEN_NEURONS: usize = 5; // The number of hidden neurons in the NN
+pub const LEARNING_RATE: f64 = 0.01; //The learning rate of the NN
+pub const OUTPUT_BIAS: f64 = 0.0;
+pub const NOISE: f64 = 0.01; // Weight noise added during training for regularization
+const INPUT_SIZE: usize = 54; // One value per sticker
+pub const NUM_MOVES: usize = 12; // Outputs of the policy head, in the order of all_moves
+
+// Get the data from a dataset file written by the generate_data binary
+pub fn get_data(file_path: &str) -> (Vec<Vec<f64>>, Vec<f64>) {
+    let samples = read_dataset(file_path).expect("Error reading the file!");
+
+    let inputs = samples
+        .iter()
+        .map(|sample| sample.cube.to_input_vector())
+        .collect();
+    let outputs = samples
+        .iter()
+        .map(|sample| sample.distance as f64 / SCALE)
+        .collect(); //Normalization
+    (inputs, outputs)
+}
+
+// A function to test the neural network and its accuracy. Only counts exact
+// hits; see EvaluationReport for the error distribution
+pub fn test_nn(prediction: &[f64], real: &[f64]) -> f64 {
+    let mut value: f64 = 0.0;
+    for i in 0..real.len() {
+        let scaled_prediction = (prediction[i] * SCALE).round(); //Rescaling and rounding
+        let scaled_real = real[i] * SCALE; //Rescaling
+
+        //println!("Predict: {} and Real: {}", scaled_prediction, scaled_real); //optional priniting of predictions and the correct anwser
+        if (scaled_prediction - scaled_real).abs() == 0.0 {
+            // this is the error measuring
+            value += 1.0;
+        }
+    }
+    value / real.len() as f64 // returns the accuracy of the NN
+}
+
+// Define a Neural Network
+#[derive(Debug, Clone, PartialEq)]
+pub struct NeuralNetwork {
+    input_weights: Vec<f64>,  // Weights between input and hidden layer
+    hidden_weights: Vec<f64>, // Weights between hidden layer and output
+    hidden_bias: Vec<f64>,    // Bias for hidden layer
+    output_bias: f64,         // Bias for output layer
+    policy_weights: Vec<f64>, // Weights between hidden layer and the move scores
+    policy_bias: Vec<f64>,    // Bias for the move scores
+    pub learning_rate: f64,
+    pub noise: f64,        // Amplitude of the weight noise added while training
+    hidden_neurons: usize, // Number of hidden neurons
+}
+
+impl NeuralNetwork {
+    pub fn new(hidden_neurons: usize) -> Self {
+        Self::with_seed(hidden_neurons, rand::thread_rng().gen())
+    }
+
+    pub fn with_seed(hidden_neurons: usize, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        // Every weight is drawn separately, otherwise all hidden neurons start out identical
+        let input_weights = (0..INPUT_SIZE * hidden_neurons)
+            .map(|_| rng.gen_range(-1.0..1.0) / (INPUT_SIZE as f64).sqrt()) // Xavier Initialisation
+            .collect();
+        let hidden_weights = (0..hidden_neurons)
+            .map(|_| rng.gen_range(-1.0..1.0) / (hidden_neurons as f64).sqrt()) // Xavier Initialisation
+            .collect();
+
+        let hidden_bias = vec![0.0; hidden_neurons]; // Set the hidden bias to 0
+        let policy_weights = (0..NUM_MOVES * hidden_neurons)
+            .map(|_| rng.gen_range(-1.0..1.0) / (hidden_neurons as f64).sqrt()) // Xavier Initialisation
+            .collect();
+
+        Self {
+            input_weights,
+            hidden_weights,
+            hidden_bias,
+            output_bias: OUTPUT_BIAS,
+            policy_weights,
+            policy_bias: vec![0.0; NUM_MOVES],
+            learning_rate: LEARNING_RATE,
+            noise: NOISE,
+            hidden_neurons,
+        }
+    }
+
+    fn hidden_layer(&self, input: &[f64]) -> Vec<f64> {
+        let mut hidden_layer_output = vec![];
+        for i in 0..self.hidden_neurons {
+            let sum: f64 = input
+                .iter()
+                .enumerate()
+                .map(|(j, &x)| x * self.input_weights[i * INPUT_SIZE + j]) // Use the input size to calculate weighted sum
+                .sum();
+            let sum_with_bias = sum + self.hidden_bias[i];
+            hidden_layer_output.push(relu(sum_with_bias));
+        }
+        hidden_layer_output
+    }
+
+    pub fn predict(&self, input: &[f64]) -> f64 {
+        // Feedforward pass
+        let hidden_layer_output = self.hidden_layer(input);
+
+        let output_sum = hidden_layer_output
+            .iter()
+            .zip(self.hidden_weights.iter())
+            .map(|(h, w)| h * w)
+            .sum::<f64>()
+            + self.output_bias;
+        sigmoid(output_sum)
+    }
+
+    // Softmax over the move scores of the policy head
+    fn policy_output(&self, hidden_layer_output: &[f64]) -> Vec<f64> {
+        let scores: Vec<f64> = (0..NUM_MOVES)
+            .map(|a| {
+                hidden_layer_output
+                    .iter()
+                    .enumerate()
+                    .map(|(j, h)| h * self.policy_weights[a * self.hidden_neurons + j])
+                    .sum::<f64>()
+                    + self.policy_bias[a]
+            })
+            .collect();
+        let max_score = scores.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let exp_scores: Vec<f64> = scores.iter().map(|s| (s - max_score).exp()).collect();
+        let total: f64 = exp_scores.iter().sum();
+        exp_scores.iter().map(|e| e / total).collect()
+    }
+
+    // Probability of each move in all_moves being the best next move
+    pub fn policy(&self, cube: &RubiksCube) -> Vec<f64> {
+        self.policy_output(&self.hidden_layer(&cube.to_input_vector()))
+    }
+
+    // Estimated number of moves needed to solve the cube, zero when solved
+    pub fn cost_to_go(&self, cube: &RubiksCube) -> f64 {
+        if cube.is_solved() {
+            return 0.0;
+        }
+        self.predict(&cube.to_input_vector()) * SCALE
+    }
+
+    //Function for training the neural network
+    pub fn train(&mut self, inputs: Vec<Vec<f64>>, outputs: Vec<f64>, epochs: usize) {
+        self.train_with_rng(inputs, outputs, epochs, &mut rand::thread_rng());
+    }
+
+    // Same as train, with the weight noise drawn from `rng`, so that seeded
+    // runs can be repeated
+    #[allow(clippy::needless_range_loop)]
+    pub fn train_with_rng<R: Rng>(
+        &mut self,
+        inputs: Vec<Vec<f64>>,
+        outputs: Vec<f64>,
+        epochs: usize,
+        rng: &mut R,
+    ) {
+        for _ in 0..epochs {
+            //Iterate over each input vector and its corresponding output
+            for (i, input_vector) in inputs.iter().enumerate() {
+                let mut hidden_layer_output = vec![];
+                //Loop thorugh each neuron in the hidden layer
+                for j in 0..self.hidden_neurons {
+                    let sum: f64 = input_vector
+                        .iter()
+                        .enumerate()
+                        .map(|(k, &x)| x * self.input_weights[j * INPUT_SIZE + k])
+                        .sum();
+                    let sum_with_bias = sum + self.hidden_bias[j];
+                    // Apply the ReLU activation function and store the result
+                    hidden_layer_output.push(relu(sum_with_bias));
+                }
+                // Compute the output sum by combining the hidden layer output and the hidden-to-output weights
+                let output_sum = hidden_layer_output
+                    .iter()
+                    .zip(self.hidden_weights.iter())
+                    .map(|(h, w)| h * w)
+                    .sum::<f64>()
+                    + self.output_bias;
+                // Apply the sigmoid activation function to get the final prediction
+                let prediction = sigmoid(output_sum);
+
+                let output_error = outputs[i] - prediction;
+                let output_delta = output_error * sigmoid_derivative(prediction); // Calculate the output layer delta (gradient for backpropagation)
+
+                // Update the hidden-to-output weights, adding noise for regularization
+                for j in 0..self.hidden_neurons {
+                    self.hidden_weights[j] +=
+                        self.learning_rate * output_delta * hidden_layer_output[j]
+                            + self.sample_noise(rng);
+                }
+                self.output_bias += self.learning_rate * output_delta;
+
+                // Update the input-to-hidden layer weights, with added noise for regularization
+                for j in 0..self.hidden_neurons {
+                    let hidden_error = output_delta
+                        * self.hidden_weights[j]
+                        * relu_derivative(hidden_layer_output[j]);
+                    // Update each input-to-hidden weight
+                    for k in 0..INPUT_SIZE {
+                        self.input_weights[j * INPUT_SIZE + k] +=
+                            self.learning_rate * hidden_error * input_vector[k]
+                                + self.sample_noise(rng);
+                    }
+                    // Update the hidden layer bias
+                    self.hidden_bias[j] += self.learning_rate * hidden_error;
+                }
+            }
+        }
+    }
+
+    // Fit the policy head to the index of the best move for each input
+    // (cross-entropy loss, also updates the shared hidden layer)
+    #[allow(clippy::needless_range_loop)]
+    pub fn train_policy(&mut self, inputs: &[Vec<f64>], best_moves: &[usize], epochs: usize) {
+        for _ in 0..epochs {
+            for (input_vector, &best_move) in inputs.iter().zip(best_moves) {
+                let hidden_layer_output = self.hidden_layer(input_vector);
+                let probabilities = self.policy_output(&hidden_layer_output);
+                // Gradient of the cross-entropy with respect to the move scores
+                let deltas: Vec<f64> = probabilities
+                    .iter()
+                    .enumerate()
+                    .map(|(a, p)| if a == best_move { 1.0 } else { 0.0 } - p)
+                    .collect();
+
+                for j in 0..self.hidden_neurons {
+                    let hidden_error = deltas
+                        .iter()
+                        .enumerate()
+                        .map(|(a, d)| d * self.policy_weights[a * self.hidden_neurons + j])
+                        .sum::<f64>()
+                        * relu_derivative(hidden_layer_output[j]);
+                    for (a, d) in deltas.iter().enumerate() {
+                        self.policy_weights[a * self.hidden_neurons + j] +=
+                            self.learning_rate * d * hidden_layer_output[j];
+                    }
+                    for (k, x) in input_vector.iter().enumerate() {
+                        self.input_weights[j * INPUT_SIZE + k] +=
+                            self.learning_rate * hidden_error * x;
+                    }
+                    self.hidden_bias[j] += self.learning_rate * hidden_error;
+                }
+                for (a, d) in deltas.iter().enumerate() {
+                    self.policy_bias[a] += self.learning_rate * d;
+                }
+            }
+        }
+    }
+
+    fn sample_noise<R: Rng>(&self, rng: &mut R) -> f64 {
+        if self.noise > 0.0 {
+            rng.gen_range(-self.noise..self.noise)
+        } else {
+            0.0
+        }
+    }
+
+    // Store the weights as plain text: the number of hidden neurons, the output
+    // bias and then one line each for input weights, hidden weights, hidden bias,
+    // policy weights and policy bias
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let join = |values: &[f64]| {
+            values
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let mut file = fs::File::create(path)?;
+        writeln!(file, "{}", self.hidden_neurons)?;
+        writeln!(file, "{}", self.output_bias)?;
+        writeln!(file, "{}", join(&self.input_weights))?;
+        writeln!(file, "{}", join(&self.hidden_weights))?;
+        writeln!(file, "{}", join(&self.hidden_bias))?;
+        writeln!(file, "{}", join(&self.policy_weights))?;
+        writeln!(file, "{}", join(&self.policy_bias))?;
+        file.flush()
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_string());
+        let mut lines = content.lines();
+        let mut next_line = |name: &str| {
+            lines
+                .next()
+                .ok_or_else(|| invalid(&format!("missing {}", name)))
+        };
+        let parse_values = |line: &str, len: usize, name: &str| {
+            let values = line
+                .split_whitespace()
+                .map(|v| v.parse::<f64>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| invalid(&format!("invalid {}", name)))?;
+            if values.len() != len {
+                return Err(invalid(&format!("wrong number of {}", name)));
+            }
+            Ok(values)
+        };
+
+        let hidden_neurons = next_line("hidden neurons")?
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| invalid("invalid hidden neurons"))?;
+        let output_bias = next_line("output bias")?
+            .trim()
+            .parse::<f64>()
+            .map_err(|_| invalid("invalid output bias"))?;
+        let input_weights = parse_values(
+            next_line("input weights")?,
+            INPUT_SIZE * hidden_neurons,
+            "input weights",
+        )?;
+        let hidden_weights = parse_values(
+            next_line("hidden weights")?,
+            hidden_neurons,
+            "hidden weights",
+        )?;
+        let hidden_bias = parse_values(next_line("hidden bias")?, hidden_neurons, "hidden bias")?;
+        // Files written before the policy head existed get a uniform policy
+        let policy_weights = match lines.next() {
+            Some(line) => parse_values(line, NUM_MOVES * hidden_neurons, "policy weights")?,
+            None => vec![0.0; NUM_MOVES * hidden_neurons],
+        };
+        let policy_bias = match lines.next() {
+            Some(line) => parse_values(line, NUM_MOVES, "policy bias")?,
+            None => vec![0.0; NUM_MOVES],
+        };
+
+        Ok(Self {
+            input_weights,
+            hidden_weights,
+            hidden_bias,
+            output_bias,
+            policy_weights,
+            policy_bias,
+            learning_rate: LEARNING_RATE,
+            noise: NOISE,
+            hidden_neurons,
+        })
+    }
+}
+
+impl CostToGo for NeuralNetwork {
+    fn cost_to_go(&self, cube: &RubiksCube) -> f64 {
+        NeuralNetwork::cost_to_go(self, cube)
+    }
+}
+
+impl Policy for NeuralNetwork {
+    fn policy(&self, cube: &RubiksCube) -> Vec<f64> {
+        NeuralNetwork::policy(self, cube)
+    }
+}
+
+// Supervised training on dataset files written by the generate_data binary
+pub fn run(train_path: &str, test_path: &str) -> NeuralNetwork {
+    // Data from files
+    let (inputs, outputs) = get_data(train_path);
+    let (test_input, test_ouput) = get_data(test_path);
+
+    // create a NN
+    let mut neural_network = NeuralNetwork::new(HIDDEN_NEURONS);
+
+    // Predictions of the NN before traning
+    let predictions: Vec<f64> = test_input
+        .iter()
+        .map(|input_vector| neural_network.predict(input_vector))
+        .collect();
+
+    println!(
+        "Test before training: {}",
+        test_nn(&predictions, &test_ouput)
+    );
+
+    //Starting time for the training
+    let start = Instant::now();
+
+    // train the NN
+    neural_network.train(inputs, outputs, EPISODES);
+
+    //Ending time for the training
+    let duration = start.elapsed().as_secs();
+
+    // Predictions of the NN
+    let predictions: Vec<f64> = test_input
+        .iter()
+        .map(|input_vector| neural_network.predict(input_vector))
+        .collect();
+
+    println!(
+        "Test after training: {}",
+        test_nn(&predictions, &test_ouput)
+    );
+    let distances: Vec<usize> = test_ouput
+        .iter()
+        .map(|d| (d * SCALE).round() as usize)
+        .collect();
+    let scaled: Vec<f64> = predictions.iter().map(|p| p * SCALE).collect();
+    print!("{}", EvaluationReport::new(&scaled, &distances));
+    println!("Elapsed time: {:?} seconds", duration);
+    println!(
+        "The trained NN:\nEpisodes: {}\nLearning-rate: {}\nOutputbias: {}\nHidden neurons: {}",
+        EPISODES, LEARNING_RATE, OUTPUT_BIAS, HIDDEN_NEURONS
+    );
+    neural_network
+}
@@ -0,0 +1,126 @@
+//! Approximate value iteration as in DeepCubeA (Agostinelli et al., 2019).
+//!
+//! Training states are random walks from the solved cube. The target of a
+//! state is the one-step lookahead `min over moves (1 + J(next))`, where `J` is
+//! a frozen copy of the network (the target network) and `J(solved) = 0`. The
+//! target network is refreshed with the trained weights every
+//! `update_interval` iterations, so the estimates grow outwards from solved.
+//! Optionally the policy head learns the move that attains the minimum.
+use super::dataset::random_scramble;
+use super::neural_network::{NeuralNetwork, SCALE};
+use crate::rubiks::cube::RubiksCube;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+pub struct ValueIterationConfig {
+    pub iterations: usize,
+    pub batch_size: usize,
+    // Random walks are between 1 and max_depth moves long
+    pub max_depth: usize,
+    // Training passes over each batch
+    pub epochs: usize,
+    // Iterations between two updates of the target network
+    pub update_interval: usize,
+    // Also fit the policy head to the best move of the lookahead
+    pub train_policy: bool,
+    pub seed: u64,
+}
+
+pub struct IterationStats {
+    pub iteration: usize,
+    // Mean squared error (in moves) of the network on the batch before training
+    pub loss: f64,
+    pub mean_target: f64,
+    pub target_updated: bool,
+}
+
+// One-step lookahead over all moves using the target network
+pub fn bellman_target(cube: &RubiksCube, target: &NeuralNetwork) -> f64 {
+    lookahead(cube, target).0
+}
+
+// Bellman target together with the index (in all_moves) of the best move
+fn lookahead(cube: &RubiksCube, target: &NeuralNetwork) -> (f64, usize) {
+    if cube.is_solved() {
+        return (0.0, 0);
+    }
+    cube.clone()
+        .all_moves()
+        .iter()
+        .enumerate()
+        .map(|(i, (_, move_fn))| {
+            let mut next_cube = cube.clone();
+            move_fn(&mut next_cube);
+            (1.0 + target.cost_to_go(&next_cube), i)
+        })
+        .fold((f64::INFINITY, 0), |best, next| {
+            if next.0 < best.0 {
+                next
+            } else {
+                best
+            }
+        })
+}
+
+// Trains `network` in place and reports every iteration to `progress`
+pub fn train_value_iteration<F: FnMut(&IterationStats)>(
+    network: &mut NeuralNetwork,
+    config: &ValueIterationConfig,
+    mut progress: F,
+) -> Result<(), String> {
+    if config.batch_size == 0 {
+        return Err("batch_size must be at least 1".to_string());
+    }
+    if config.max_depth == 0 {
+        return Err("max_depth must be at least 1".to_string());
+    }
+    if config.update_interval == 0 {
+        return Err("update_interval must be at least 1".to_string());
+    }
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut target = network.clone();
+
+    for iteration in 1..=config.iterations {
+        let states: Vec<RubiksCube> = (0..config.batch_size)
+            .map(|_| {
+                let depth = rng.gen_range(1..=config.max_depth);
+                let mut cube = RubiksCube::new();
+                cube.apply_scramble(&random_scramble(&mut rng, depth).join(" "));
+                cube
+            })
+            .collect();
+        let lookaheads: Vec<(f64, usize)> =
+            states.iter().map(|cube| lookahead(cube, &target)).collect();
+        // The network output is a sigmoid, so targets beyond SCALE can't be reached
+        let targets: Vec<f64> = lookaheads.iter().map(|(t, _)| t.min(SCALE)).collect();
+
+        let loss = states
+            .iter()
+            .zip(&targets)
+            .map(|(cube, t)| (network.cost_to_go(cube) - t).powi(2))
+            .sum::<f64>()
+            / states.len() as f64;
+        let mean_target = targets.iter().sum::<f64>() / targets.len() as f64;
+
+        let inputs: Vec<Vec<f64>> = states.iter().map(|cube| cube.to_input_vector()).collect();
+        if config.train_policy {
+            let best_moves: Vec<usize> = lookaheads.iter().map(|&(_, a)| a).collect();
+            network.train_policy(&inputs, &best_moves, config.epochs);
+        }
+        let outputs = targets.iter().map(|t| t / SCALE).collect();
+        network.train_with_rng(inputs, outputs, config.epochs, &mut rng);
+
+        let target_updated = iteration % config.update_interval == 0;
+        if target_updated {
+            target = network.clone();
+        }
+
+        progress(&IterationStats {
+            iteration,
+            loss,
+            mean_target,
+            target_updated,
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,412 @@
+//! Pictures of cube states for tutorials, drawn without a window.
+//!
+//! A view of the cube is first laid out as a `Diagram`: filled polygons,
+//! arrows and letters in pixel coordinates. The diagram is then written as
+//! SVG or rasterized into a PNG. Three views are available: the net the
+//! visualizer shows, an isometric view of the U, F and R faces, and the U
+//! face seen from above with the top row of the side faces around it, on
+//! which arrows can show how the last layer pieces move.
+mod png;
+mod svg;
+
+use crate::rubiks::cube::RubiksCube;
+use crate::rubiks::cubie::{CubieCube, CORNER_FACELETS, EDGE_FACELETS};
+use crate::ui::scheme::ColorScheme;
+use crate::ui::view3d::{sticker_corners, Vec3};
+use eframe::egui::Color32;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+pub type Point = (f32, f32);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    Polygon {
+        points: Vec<Point>,
+        fill: Color32,
+        // Width and colour of the outline
+        stroke: Option<(f32, Color32)>,
+    },
+    // Line with a head at `to`, and at `from` too if `both`
+    Arrow {
+        from: Point,
+        to: Point,
+        both: bool,
+        width: f32,
+        color: Color32,
+    },
+    // Letter centred on `center`, `size` pixels high
+    Letter {
+        center: Point,
+        size: f32,
+        letter: char,
+        color: Color32,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagram {
+    pub width: f32,
+    pub height: f32,
+    // Drawn in order, later shapes on top
+    pub shapes: Vec<Shape>,
+}
+
+impl Diagram {
+    pub fn new(width: f32, height: f32) -> Self {
+        Diagram {
+            width,
+            height,
+            shapes: Vec::new(),
+        }
+    }
+
+    // Writes the diagram as SVG or PNG, depending on the extension of `path`
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        let bytes = match extension.to_ascii_lowercase().as_str() {
+            "svg" => self.to_svg().into_bytes(),
+            "png" => self.to_png()?,
+            _ => return Err(format!("{}: expected a .svg or .png file", path.display())),
+        };
+        fs::write(path, bytes).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum View {
+    Net,
+    Isometric,
+    // The U face from above with the side stickers of the last layer
+    LastLayer,
+}
+
+impl View {
+    pub const ALL: [View; 3] = [View::Net, View::Isometric, View::LastLayer];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            View::Net => "net",
+            View::Isometric => "isometric",
+            View::LastLayer => "last-layer",
+        }
+    }
+}
+
+impl FromStr for View {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        View::ALL
+            .into_iter()
+            .find(|view| view.name() == s)
+            .ok_or_else(|| format!("unknown view {}, expected net, isometric or last-layer", s))
+    }
+}
+
+// Arrow between two stickers of the U face, shown in the last layer view
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Arrow {
+    pub from: usize,
+    pub to: usize,
+    // Head at both ends, for two pieces that swap places
+    pub both: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderOptions {
+    pub view: View,
+    pub scheme: ColorScheme,
+    // Edge length of a sticker in pixels
+    pub sticker_size: f32,
+    pub arrows: Vec<Arrow>,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            view: View::Net,
+            scheme: ColorScheme::default(),
+            sticker_size: 40.0,
+            arrows: Vec::new(),
+        }
+    }
+}
+
+// Where the faces are in the net, in faces relative to the front face
+const NET_FACE_POSITIONS: [(i32, i32); 6] = [
+    (0, -1), // Top
+    (0, 1),  // Bottom
+    (0, 0),  // Front
+    (2, 0),  // Back
+    (1, 0),  // Right
+    (-1, 0), // Left
+];
+
+// Top left corner of a sticker in the net, in stickers from the top left
+// corner of the front face. The net spans -3..9 horizontally and -3..6
+// vertically.
+pub fn net_sticker_offset(face: usize, index: usize) -> (i32, i32) {
+    let (dx, dy) = NET_FACE_POSITIONS[face];
+    (dx * 3 + (index % 3) as i32, dy * 3 + (index / 3) as i32)
+}
+
+// Gaps between stickers, as a fraction of the sticker size
+const STICKER_GAP: f32 = 0.06;
+const OUTLINE: Color32 = Color32::from_rgb(30, 30, 30);
+
+fn sticker(
+    shapes: &mut Vec<Shape>,
+    scheme: &ColorScheme,
+    cube: &RubiksCube,
+    (face, index): (usize, usize),
+    points: Vec<Point>,
+    size: f32,
+) {
+    let color = cube.faces[face][index];
+    let center = (
+        points.iter().map(|p| p.0).sum::<f32>() / points.len() as f32,
+        points.iter().map(|p| p.1).sum::<f32>() / points.len() as f32,
+    );
+    shapes.push(Shape::Polygon {
+        points,
+        fill: scheme.color(color),
+        stroke: Some((size * STICKER_GAP, OUTLINE)),
+    });
+    if scheme.show_letters {
+        shapes.push(Shape::Letter {
+            center,
+            size: size * 0.5,
+            letter: scheme.letter(color),
+            color: scheme.text_color(color),
+        });
+    }
+}
+
+fn square(x: f32, y: f32, width: f32, height: f32) -> Vec<Point> {
+    vec![
+        (x, y),
+        (x + width, y),
+        (x + width, y + height),
+        (x, y + height),
+    ]
+}
+
+fn render_net(cube: &RubiksCube, options: &RenderOptions) -> Diagram {
+    let size = options.sticker_size;
+    let margin = size * 0.25;
+    let mut diagram = Diagram::new(12.0 * size + 2.0 * margin, 9.0 * size + 2.0 * margin);
+    for face in 0..6 {
+        for index in 0..9 {
+            let (x, y) = net_sticker_offset(face, index);
+            let points = square(
+                margin + (x + 3) as f32 * size,
+                margin + (y + 3) as f32 * size,
+                size,
+                size,
+            );
+            sticker(
+                &mut diagram.shapes,
+                &options.scheme,
+                cube,
+                (face, index),
+                points,
+                size,
+            );
+        }
+    }
+    diagram
+}
+
+// Orthographic view from the corner between U, F and R: x goes to the
+// lower right, z to the lower left and y straight up
+fn isometric(p: Vec3) -> Point {
+    let sqrt2 = 2f32.sqrt();
+    let sqrt6 = 6f32.sqrt();
+    ((p[0] - p[2]) / sqrt2, -(2.0 * p[1] - p[0] - p[2]) / sqrt6)
+}
+
+fn render_isometric(cube: &RubiksCube, options: &RenderOptions) -> Diagram {
+    let size = options.sticker_size;
+    let margin = size * 0.25;
+    // Corners of the cube are at ±1.5
+    let half_width = 3.0 / 2f32.sqrt();
+    let half_height = 6.0 / 6f32.sqrt();
+    let mut diagram = Diagram::new(
+        2.0 * (half_width * size + margin),
+        2.0 * (half_height * size + margin),
+    );
+    let to_screen = |p: Vec3| {
+        let (x, y) = isometric(p);
+        (
+            margin + (half_width + x) * size,
+            margin + (half_height + y) * size,
+        )
+    };
+    // U, F and R
+    for face in [0, 2, 4] {
+        for index in 0..9 {
+            let points = sticker_corners(face, index, 0.0).map(to_screen).to_vec();
+            sticker(
+                &mut diagram.shapes,
+                &options.scheme,
+                cube,
+                (face, index),
+                points,
+                size,
+            );
+        }
+    }
+    diagram
+}
+
+// Side stickers are this thick, in stickers
+const SIDE_THICKNESS: f32 = 0.4;
+
+fn render_last_layer(cube: &RubiksCube, options: &RenderOptions) -> Diagram {
+    let size = options.sticker_size;
+    let margin = size * 0.25;
+    let gap = size * 0.1;
+    let side = size * SIDE_THICKNESS;
+    let extent = 2.0 * (margin + side + gap) + 3.0 * size;
+    let mut diagram = Diagram::new(extent, extent);
+    // Top left corner of the U face
+    let origin = margin + side + gap;
+    let u_sticker = |index: usize| {
+        (
+            origin + (index % 3) as f32 * size,
+            origin + (index / 3) as f32 * size,
+        )
+    };
+    let shapes = &mut diagram.shapes;
+    let scheme = &options.scheme;
+    for index in 0..9 {
+        let (x, y) = u_sticker(index);
+        sticker(
+            shapes,
+            scheme,
+            cube,
+            (0, index),
+            square(x, y, size, size),
+            size,
+        );
+    }
+    // The top row of every side face, next to the U stickers it touches
+    let far = origin + 3.0 * size + gap;
+    for j in 0..3 {
+        let along = |k: usize| origin + k as f32 * size;
+        let sides = [
+            (3, square(along(2 - j), margin, size, side)),
+            (2, square(along(j), far, size, side)),
+            (4, square(far, along(2 - j), side, size)),
+            (5, square(margin, along(j), side, size)),
+        ];
+        for (face, points) in sides {
+            sticker(shapes, scheme, cube, (face, j), points, size);
+        }
+    }
+
+    for arrow in &options.arrows {
+        let center = |index: usize| {
+            let (x, y) = u_sticker(index);
+            (x + size / 2.0, y + size / 2.0)
+        };
+        let (from, to) = (center(arrow.from), center(arrow.to));
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let length = (dx * dx + dy * dy).sqrt();
+        if length == 0.0 {
+            continue;
+        }
+        // Keep the ends off the sticker centres
+        let trim = size * 0.2 / length;
+        shapes.push(Shape::Arrow {
+            from: (from.0 + dx * trim, from.1 + dy * trim),
+            to: (to.0 - dx * trim, to.1 - dy * trim),
+            both: arrow.both,
+            width: size * 0.08,
+            color: OUTLINE,
+        });
+    }
+    diagram
+}
+
+pub fn render(cube: &RubiksCube, options: &RenderOptions) -> Diagram {
+    match options.view {
+        View::Net => render_net(cube, options),
+        View::Isometric => render_isometric(cube, options),
+        View::LastLayer => render_last_layer(cube, options),
+    }
+}
+
+// Arrows from every last layer piece that is out of place to its solved
+// position, with pieces that swap places sharing a double headed arrow.
+// Empty if the state isn't valid.
+pub fn pll_arrows(cube: &RubiksCube) -> Vec<Arrow> {
+    let Ok(cubie) = CubieCube::from_facelets(cube) else {
+        return Vec::new();
+    };
+    // The U pieces come first, with their U facelet first
+    let corner_stickers = CORNER_FACELETS.map(|facelets| facelets[0].1);
+    let edge_stickers = EDGE_FACELETS.map(|facelets| facelets[0].1);
+    let mut arrows = Vec::new();
+    piece_arrows(&cubie.corner_perm[..4], &corner_stickers, &mut arrows);
+    piece_arrows(&cubie.edge_perm[..4], &edge_stickers, &mut arrows);
+    arrows
+}
+
+// `perm` has the piece in each of the U positions
+fn piece_arrows(perm: &[u8], stickers: &[usize], arrows: &mut Vec<Arrow>) {
+    for (position, &piece) in perm.iter().enumerate() {
+        let piece = piece as usize;
+        // Pieces from the other layers have no place to point to
+        if piece == position || piece >= perm.len() {
+            continue;
+        }
+        let both = perm[piece] as usize == position;
+        if both && piece < position {
+            continue;
+        }
+        arrows.push(Arrow {
+            from: stickers[position],
+            to: stickers[piece],
+            both,
+        });
+    }
+}
+
+// Shaft and heads of an arrow: the shaft stops where a head starts
+fn arrow_geometry(
+    from: Point,
+    to: Point,
+    both: bool,
+    width: f32,
+) -> ((Point, Point), Vec<[Point; 3]>) {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let length = (dx * dx + dy * dy).sqrt().max(f32::EPSILON);
+    let (ux, uy) = (dx / length, dy / length);
+    let (head_length, head_width) = (width * 3.5, width * 2.0);
+    let head = |tip: Point, direction: f32| {
+        let base = (
+            tip.0 - ux * head_length * direction,
+            tip.1 - uy * head_length * direction,
+        );
+        [
+            tip,
+            (base.0 - uy * head_width, base.1 + ux * head_width),
+            (base.0 + uy * head_width, base.1 - ux * head_width),
+        ]
+    };
+    let mut heads = vec![head(to, 1.0)];
+    let mut start = from;
+    if both {
+        heads.push(head(from, -1.0));
+        start = (from.0 + ux * head_length, from.1 + uy * head_length);
+    }
+    let end = (to.0 - ux * head_length, to.1 - uy * head_length);
+    ((start, end), heads)
+}
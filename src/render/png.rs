@@ -0,0 +1,183 @@
+use super::{arrow_geometry, Diagram, Point, Shape};
+use ab_glyph::{Font, FontRef, OutlineCurve};
+use eframe::egui::{Color32, FontDefinitions};
+use tiny_skia::{FillRule, LineCap, LineJoin, Paint, PathBuilder, Pixmap, Stroke, Transform};
+
+fn paint(color: Color32) -> Paint<'static> {
+    let mut paint = Paint::default();
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    paint.set_color_rgba8(r, g, b, a);
+    paint.anti_alias = true;
+    paint
+}
+
+fn fill_polygon(pixmap: &mut Pixmap, points: &[Point], color: Color32) {
+    if let Some(path) = polygon_path(points) {
+        pixmap.fill_path(
+            &path,
+            &paint(color),
+            FillRule::Winding,
+            Transform::identity(),
+            None,
+        );
+    }
+}
+
+fn polygon_path(points: &[Point]) -> Option<tiny_skia::Path> {
+    let (&(x, y), rest) = points.split_first()?;
+    let mut builder = PathBuilder::new();
+    builder.move_to(x, y);
+    for &(x, y) in rest {
+        builder.line_to(x, y);
+    }
+    builder.close();
+    builder.finish()
+}
+
+// Fills the outline of `letter` from the font of the visualizer
+fn draw_letter(
+    pixmap: &mut Pixmap,
+    font: &FontRef,
+    center: Point,
+    size: f32,
+    letter: char,
+    color: Color32,
+) {
+    let Some(outline) = font.outline(font.glyph_id(letter)) else {
+        return;
+    };
+    let scale = size / font.units_per_em().unwrap_or(1000.0);
+    // Font units point up; centre the glyph's bounding box
+    let middle = (
+        (outline.bounds.min.x + outline.bounds.max.x) / 2.0,
+        (outline.bounds.min.y + outline.bounds.max.y) / 2.0,
+    );
+    let to_pixels = |p: ab_glyph::Point| {
+        (
+            center.0 + (p.x - middle.0) * scale,
+            center.1 - (p.y - middle.1) * scale,
+        )
+    };
+
+    let mut builder = PathBuilder::new();
+    let mut last: Option<Point> = None;
+    for curve in &outline.curves {
+        let (start, end) = match *curve {
+            OutlineCurve::Line(a, b) => (a, b),
+            OutlineCurve::Quad(a, _, b) => (a, b),
+            OutlineCurve::Cubic(a, _, _, b) => (a, b),
+        };
+        let start = to_pixels(start);
+        // A new contour starts wherever the previous curve didn't end
+        if last != Some(start) {
+            if last.is_some() {
+                builder.close();
+            }
+            builder.move_to(start.0, start.1);
+        }
+        match *curve {
+            OutlineCurve::Line(_, _) => {
+                let (x, y) = to_pixels(end);
+                builder.line_to(x, y);
+            }
+            OutlineCurve::Quad(_, c, _) => {
+                let (c, e) = (to_pixels(c), to_pixels(end));
+                builder.quad_to(c.0, c.1, e.0, e.1);
+            }
+            OutlineCurve::Cubic(_, c1, c2, _) => {
+                let (c1, c2, e) = (to_pixels(c1), to_pixels(c2), to_pixels(end));
+                builder.cubic_to(c1.0, c1.1, c2.0, c2.1, e.0, e.1);
+            }
+        }
+        last = Some(to_pixels(end));
+    }
+    builder.close();
+    if let Some(path) = builder.finish() {
+        pixmap.fill_path(
+            &path,
+            &paint(color),
+            FillRule::Winding,
+            Transform::identity(),
+            None,
+        );
+    }
+}
+
+impl Diagram {
+    // PNG with a transparent background, one pixel per unit
+    pub fn to_png(&self) -> Result<Vec<u8>, String> {
+        let mut pixmap = Pixmap::new(self.width.ceil() as u32, self.height.ceil() as u32)
+            .ok_or_else(|| format!("can't draw a {}x{} image", self.width, self.height))?;
+        let fonts = FontDefinitions::default();
+        let font = fonts
+            .font_data
+            .get("Hack")
+            .and_then(|data| FontRef::try_from_slice(&data.font).ok());
+
+        for shape in &self.shapes {
+            match shape {
+                Shape::Polygon {
+                    points,
+                    fill,
+                    stroke,
+                } => {
+                    fill_polygon(&mut pixmap, points, *fill);
+                    if let (Some((width, color)), Some(path)) = (stroke, polygon_path(points)) {
+                        let stroke = Stroke {
+                            width: *width,
+                            line_join: LineJoin::Round,
+                            ..Stroke::default()
+                        };
+                        pixmap.stroke_path(
+                            &path,
+                            &paint(*color),
+                            &stroke,
+                            Transform::identity(),
+                            None,
+                        );
+                    }
+                }
+                Shape::Arrow {
+                    from,
+                    to,
+                    both,
+                    width,
+                    color,
+                } => {
+                    let ((start, end), heads) = arrow_geometry(*from, *to, *both, *width);
+                    let mut builder = PathBuilder::new();
+                    builder.move_to(start.0, start.1);
+                    builder.line_to(end.0, end.1);
+                    if let Some(path) = builder.finish() {
+                        let stroke = Stroke {
+                            width: *width,
+                            line_cap: LineCap::Butt,
+                            ..Stroke::default()
+                        };
+                        pixmap.stroke_path(
+                            &path,
+                            &paint(*color),
+                            &stroke,
+                            Transform::identity(),
+                            None,
+                        );
+                    }
+                    for head in heads {
+                        fill_polygon(&mut pixmap, &head, *color);
+                    }
+                }
+                Shape::Letter {
+                    center,
+                    size,
+                    letter,
+                    color,
+                } => {
+                    if let Some(font) = &font {
+                        draw_letter(&mut pixmap, font, *center, *size, *letter, *color);
+                    }
+                }
+            }
+        }
+        pixmap.encode_png().map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,109 @@
+use super::{arrow_geometry, Diagram, Point, Shape};
+use eframe::egui::Color32;
+use std::fmt::{self, Write};
+
+fn color(c: Color32) -> String {
+    format!("#{:02x}{:02x}{:02x}", c.r(), c.g(), c.b())
+}
+
+fn points(points: &[Point]) -> String {
+    points
+        .iter()
+        .map(|(x, y)| format!("{:.2},{:.2}", x, y))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn escape(letter: char) -> String {
+    match letter {
+        '<' => "&lt;".to_string(),
+        '>' => "&gt;".to_string(),
+        '&' => "&amp;".to_string(),
+        c => c.to_string(),
+    }
+}
+
+impl Diagram {
+    pub fn to_svg(&self) -> String {
+        let mut svg = String::new();
+        self.write_svg(&mut svg)
+            .expect("writing to a String can't fail");
+        svg
+    }
+
+    fn write_svg(&self, svg: &mut String) -> fmt::Result {
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w:.0}" height="{h:.0}" viewBox="0 0 {w:.2} {h:.2}">"#,
+            w = self.width,
+            h = self.height
+        )?;
+        for shape in &self.shapes {
+            match shape {
+                Shape::Polygon {
+                    points: p,
+                    fill,
+                    stroke,
+                } => {
+                    let stroke = match stroke {
+                        Some((width, c)) => format!(
+                            r#" stroke="{}" stroke-width="{:.2}" stroke-linejoin="round""#,
+                            color(*c),
+                            width
+                        ),
+                        None => String::new(),
+                    };
+                    writeln!(
+                        svg,
+                        r#"  <polygon points="{}" fill="{}"{}/>"#,
+                        points(p),
+                        color(*fill),
+                        stroke
+                    )?;
+                }
+                Shape::Arrow {
+                    from,
+                    to,
+                    both,
+                    width,
+                    color: c,
+                } => {
+                    let ((start, end), heads) = arrow_geometry(*from, *to, *both, *width);
+                    writeln!(
+                        svg,
+                        r#"  <line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" stroke="{}" stroke-width="{:.2}"/>"#,
+                        start.0,
+                        start.1,
+                        end.0,
+                        end.1,
+                        color(*c),
+                        width
+                    )?;
+                    for head in heads {
+                        writeln!(
+                            svg,
+                            r#"  <polygon points="{}" fill="{}"/>"#,
+                            points(&head),
+                            color(*c)
+                        )?;
+                    }
+                }
+                Shape::Letter {
+                    center,
+                    size,
+                    letter,
+                    color: c,
+                } => writeln!(
+                    svg,
+                    r#"  <text x="{:.2}" y="{:.2}" font-family="sans-serif" font-size="{:.2}" text-anchor="middle" dominant-baseline="central" fill="{}">{}</text>"#,
+                    center.0,
+                    center.1,
+                    size,
+                    color(*c),
+                    escape(*letter)
+                )?,
+            }
+        }
+        writeln!(svg, "</svg>")
+    }
+}
@@ -0,0 +1,192 @@
+use super::cube::RubiksCube;
+use super::metric::{Metric, MetricMove};
+use super::pruning::{StateSet, SuccessorGenerator, START};
+use super::solver::SearchResult;
+use super::table::DistanceTable;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+// Anything that estimates the number of moves left to solve a cube
+pub trait CostToGo {
+    fn cost_to_go(&self, cube: &RubiksCube) -> f64;
+
+    // Estimators that profit from batching (e.g. neural networks) can override this
+    fn cost_to_go_batch(&self, cubes: &[RubiksCube]) -> Vec<f64> {
+        cubes.iter().map(|cube| self.cost_to_go(cube)).collect()
+    }
+}
+
+impl<F: Fn(&RubiksCube) -> f64> CostToGo for F {
+    fn cost_to_go(&self, cube: &RubiksCube) -> f64 {
+        self(cube)
+    }
+}
+
+impl CostToGo for DistanceTable {
+    fn cost_to_go(&self, cube: &RubiksCube) -> f64 {
+        self.heuristic(cube) as f64
+    }
+}
+
+// Settings of the batch-weighted A* search
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AStarConfig {
+    // Nodes are ranked by g + weight * h: 1.0 is plain A*, larger is greedier
+    pub weight: f64,
+    // Number of nodes expanded together before their children are evaluated
+    pub batch_size: usize,
+    // Give up after expanding this many nodes
+    pub max_nodes: usize,
+    // Moves to search with and count g in. A distance table used as the
+    // heuristic should be built for the same metric.
+    pub metric: Metric,
+}
+
+impl Default for AStarConfig {
+    fn default() -> Self {
+        AStarConfig {
+            weight: 1.0,
+            batch_size: 100,
+            max_nodes: 1_000_000,
+            metric: Metric::Qtm,
+        }
+    }
+}
+
+struct Node {
+    cube: RubiksCube,
+    parent: Option<usize>,
+    // Index of the move from the parent
+    move_index: usize,
+    // Pruning state after the moves from the start
+    state: usize,
+    g: usize,
+}
+
+// Entry of the open list, ordered so that BinaryHeap pops the lowest f first
+struct OpenEntry {
+    f: f64,
+    g: usize,
+    node: usize,
+}
+
+impl Ord for OpenEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Lowest f first, ties go to the deeper node
+        other
+            .f
+            .total_cmp(&self.f)
+            .then_with(|| self.g.cmp(&other.g))
+    }
+}
+
+impl PartialOrd for OpenEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for OpenEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for OpenEntry {}
+
+impl RubiksCube {
+    // Batch-weighted A* search (DeepCubeA): the `batch_size` best nodes are
+    // expanded at once and all their children are evaluated in one call to
+    // the estimator. Not optimal unless weight is 1 and the estimate admissible.
+    pub fn solve_weighted_astar<H: CostToGo>(
+        &self,
+        heuristic: &H,
+        config: &AStarConfig,
+    ) -> Option<SearchResult> {
+        let generator = SuccessorGenerator::new(config.metric);
+        let mut nodes = vec![Node {
+            cube: self.clone(),
+            parent: None,
+            move_index: 0,
+            state: START,
+            g: 0,
+        }];
+        // Shortest known path to every state, and the pruning states it was
+        // reached in on paths of that length
+        let mut best_g: HashMap<RubiksCube, (usize, StateSet)> =
+            HashMap::from([(self.clone(), (0, 1 << START))]);
+        let mut open = BinaryHeap::from([OpenEntry {
+            f: config.weight * heuristic.cost_to_go(self),
+            g: 0,
+            node: 0,
+        }]);
+        let mut nodes_expanded = 0;
+
+        while !open.is_empty() && nodes_expanded < config.max_nodes {
+            let mut children = Vec::new();
+            for _ in 0..config.batch_size.max(1) {
+                let Some(entry) = open.pop() else { break };
+                let node = &nodes[entry.node];
+                // Skip entries that were superseded by a shorter path
+                if best_g[&node.cube].0 < node.g {
+                    continue;
+                }
+                if node.cube.is_solved() {
+                    return Some(SearchResult {
+                        solution: reconstruct(&nodes, generator.moves(), entry.node),
+                        nodes_expanded,
+                    });
+                }
+                nodes_expanded += 1;
+
+                for (move_index, m, state) in generator.successors(node.state) {
+                    let mut next_cube = node.cube.clone();
+                    m.apply(&mut next_cube);
+                    let g = node.g + 1;
+                    match best_g.get_mut(&next_cube) {
+                        Some((best, states))
+                            if *best < g || (*best == g && *states & 1 << state != 0) =>
+                        {
+                            continue
+                        }
+                        Some((best, states)) if *best == g => *states |= 1 << state,
+                        _ => {
+                            best_g.insert(next_cube.clone(), (g, 1 << state));
+                        }
+                    }
+                    children.push(Node {
+                        cube: next_cube,
+                        parent: Some(entry.node),
+                        move_index,
+                        state,
+                        g,
+                    });
+                }
+            }
+
+            let cubes: Vec<RubiksCube> = children.iter().map(|node| node.cube.clone()).collect();
+            let estimates = heuristic.cost_to_go_batch(&cubes);
+            for (child, h) in children.into_iter().zip(estimates) {
+                open.push(OpenEntry {
+                    f: child.g as f64 + config.weight * h,
+                    g: child.g,
+                    node: nodes.len(),
+                });
+                nodes.push(child);
+            }
+        }
+
+        None
+    }
+}
+
+fn reconstruct(nodes: &[Node], moves: &[MetricMove], mut index: usize) -> Vec<String> {
+    let mut path = Vec::new();
+    while let Some(parent) = nodes[index].parent {
+        let turns = &moves[nodes[index].move_index].turns;
+        path.extend(turns.iter().rev().map(|m| m.to_string()));
+        index = parent;
+    }
+    path.reverse();
+    path
+}
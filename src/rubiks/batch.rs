@@ -0,0 +1,501 @@
+//! Solving many scrambles in parallel.
+//!
+//! Scrambles are read one per line (`#` starts a comment) or as a JSON array
+//! of strings, the format `rubiks scramble --format json` prints. Every result
+//! is appended to a CSV file as soon as it is found:
+//!
+//! ```text
+//! index,scramble,solved,solver,metric,length,nodes_expanded,time_ms,solution
+//! 0,R U F',true,ida,qtm,3,27,0.412,F U' R'
+//! ```
+//!
+//! Solutions are written as face turns, the length is counted in the metric
+//! the solver searched in.
+//!
+//! If the file already holds results, e.g. because an earlier run was
+//! interrupted, only the scrambles without a solution are solved: rows where
+//! the solver gave up are tried again. The results must come from the same
+//! solver and metric. When all are done the file is rewritten in the order of
+//! the input.
+use super::astar::AStarConfig;
+use super::cube::RubiksCube;
+use super::kociemba::KociembaTables;
+use super::mcts::{MctsConfig, Policy};
+use super::metric::Metric;
+use super::moves::{algorithm_to_string, parse_algorithm};
+use super::solver::SearchResult;
+use super::table::DistanceTable;
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const HEADER: &str = "index,scramble,solved,solver,metric,length,nodes_expanded,time_ms,solution";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Solver {
+    Bfs,
+    Ida,
+    AStar,
+    Mcts,
+    Kociemba,
+}
+
+impl Solver {
+    pub const ALL: [Solver; 5] = [
+        Solver::Bfs,
+        Solver::Ida,
+        Solver::AStar,
+        Solver::Mcts,
+        Solver::Kociemba,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Solver::Bfs => "bfs",
+            Solver::Ida => "ida",
+            Solver::AStar => "astar",
+            Solver::Mcts => "mcts",
+            Solver::Kociemba => "kociemba",
+        }
+    }
+}
+
+impl fmt::Display for Solver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Solver {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Solver::ALL
+            .into_iter()
+            .find(|solver| solver.name() == s)
+            .ok_or_else(|| {
+                format!(
+                    "unknown solver {}, expected bfs, ida, astar, mcts or kociemba",
+                    s
+                )
+            })
+    }
+}
+
+// A solver together with everything it needs
+pub struct SolverConfig<'a, M> {
+    pub solver: Solver,
+    // Heuristic of IDA*, and of A* without a model. BFS, IDA* and A* search
+    // in the metric of the table; MCTS always makes quarter turns, its policy
+    // has one output for each of them. Kociemba makes face turns and counts
+    // its solutions in the metric of the table.
+    pub table: &'a DistanceTable,
+    // Needed by MCTS, used by A* if given
+    pub model: Option<&'a M>,
+    // Needed by Kociemba
+    pub kociemba: Option<&'a KociembaTables>,
+    // IDA* gives up beyond this many moves
+    pub max_depth: usize,
+    // Kociemba gives up if it finds no solution of at most this many face
+    // turns
+    pub max_length: usize,
+    pub astar: AStarConfig,
+    pub mcts: MctsConfig,
+}
+
+impl<M> SolverConfig<'_, M> {
+    // Metric the lengths of the solutions are counted in
+    pub fn metric(&self) -> Metric {
+        match self.solver {
+            Solver::Mcts => Metric::Qtm,
+            _ => self.table.metric(),
+        }
+    }
+}
+
+impl<M: Policy> SolverConfig<'_, M> {
+    // None if nothing was found within the limits (or MCTS has no model,
+    // Kociemba no tables)
+    pub fn solve(&self, cube: &RubiksCube) -> Option<SearchResult> {
+        match self.solver {
+            Solver::Bfs => cube.solve_bfs_with(self.table.metric()),
+            Solver::Ida => cube.solve_ida(self.table, self.max_depth),
+            Solver::AStar => {
+                let astar = AStarConfig {
+                    metric: self.table.metric(),
+                    ..self.astar
+                };
+                match self.model {
+                    Some(model) => cube.solve_weighted_astar(model, &astar),
+                    None => cube.solve_weighted_astar(self.table, &astar),
+                }
+            }
+            // Report the tree size in place of the expanded nodes
+            Solver::Mcts => cube
+                .solve_mcts(self.model?, &self.mcts)
+                .map(|result| SearchResult {
+                    solution: result.solution,
+                    nodes_expanded: result.tree_size,
+                }),
+            Solver::Kociemba => cube.solve_kociemba(self.kociemba?, self.max_length),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchResult {
+    // Position of the scramble in the input
+    pub index: usize,
+    pub scramble: String,
+    // None if the solver gave up
+    pub solution: Option<Vec<String>>,
+    pub solver: Solver,
+    // Metric the solver searched in
+    pub metric: Metric,
+    pub nodes_expanded: usize,
+    pub time: Duration,
+}
+
+// Length of a solution in face turns
+fn length_in(metric: Metric, solution: &[String]) -> Option<usize> {
+    let moves = parse_algorithm(&solution.join(" ")).ok()?;
+    Some(metric.length(&moves))
+}
+
+impl BatchResult {
+    // Length of the solution in the metric, None if unsolved
+    pub fn length(&self) -> Option<usize> {
+        length_in(self.metric, self.solution.as_ref()?)
+    }
+
+    fn to_csv(&self) -> String {
+        let (solved, length, solution) = match &self.solution {
+            Some(solution) => (
+                "true",
+                self.length().map_or(String::new(), |l| l.to_string()),
+                solution.join(" "),
+            ),
+            None => ("false", String::new(), String::new()),
+        };
+        format!(
+            "{},{},{},{},{},{},{},{:.3},{}",
+            self.index,
+            self.scramble,
+            solved,
+            self.solver,
+            self.metric,
+            length,
+            self.nodes_expanded,
+            self.time.as_secs_f64() * 1000.0,
+            solution
+        )
+    }
+
+    fn from_csv(line: &str) -> Option<Self> {
+        let fields: Vec<&str> = line.split(',').collect();
+        let [index, scramble, solved, solver, metric, length, nodes, time, solution] = fields[..]
+        else {
+            return None;
+        };
+        let solver: Solver = solver.parse().ok()?;
+        let metric: Metric = metric.parse().ok()?;
+        let solution = match solved {
+            "true" => {
+                let solution: Vec<String> = solution.split_whitespace().map(String::from).collect();
+                if length.parse::<usize>().ok()? != length_in(metric, &solution)? {
+                    return None;
+                }
+                Some(solution)
+            }
+            "false" => None,
+            _ => return None,
+        };
+        Some(BatchResult {
+            index: index.parse().ok()?,
+            scramble: scramble.to_string(),
+            solution,
+            solver,
+            metric,
+            nodes_expanded: nodes.parse().ok()?,
+            time: Duration::try_from_secs_f64(time.parse::<f64>().ok()? / 1000.0).ok()?,
+        })
+    }
+}
+
+// Reads scrambles one per line or as a JSON array of strings
+pub fn parse_scrambles(text: &str) -> Result<Vec<String>, String> {
+    let scrambles = if text.trim_start().starts_with('[') {
+        parse_json_strings(text)?
+    } else {
+        text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(String::from)
+            .collect()
+    };
+    // Store every scramble in the same spelling, which also keeps commas
+    // out of the CSV
+    scrambles
+        .iter()
+        .enumerate()
+        .map(|(i, scramble)| {
+            parse_algorithm(scramble)
+                .map(|moves| algorithm_to_string(&moves))
+                .map_err(|e| format!("scramble {}: {}", i + 1, e))
+        })
+        .collect()
+}
+
+fn parse_json_strings(text: &str) -> Result<Vec<String>, String> {
+    let invalid = || "invalid JSON, expected an array of strings".to_string();
+    let mut chars = text.trim().chars();
+    if chars.next() != Some('[') {
+        return Err(invalid());
+    }
+    let mut strings = Vec::new();
+    loop {
+        match chars.find(|c| !c.is_whitespace()) {
+            Some(']') if strings.is_empty() => break,
+            Some('"') => {}
+            _ => return Err(invalid()),
+        }
+        let mut string = String::new();
+        loop {
+            match chars.next().ok_or_else(invalid)? {
+                '"' => break,
+                '\\' => match chars.next().ok_or_else(invalid)? {
+                    'n' => string.push('\n'),
+                    't' => string.push('\t'),
+                    'r' => string.push('\r'),
+                    c @ ('"' | '\\' | '/') => string.push(c),
+                    _ => return Err(invalid()),
+                },
+                c => string.push(c),
+            }
+        }
+        strings.push(string);
+        match chars.find(|c| !c.is_whitespace()) {
+            Some(',') => {}
+            Some(']') => break,
+            _ => return Err(invalid()),
+        }
+    }
+    if chars.any(|c| !c.is_whitespace()) {
+        return Err(invalid());
+    }
+    Ok(strings)
+}
+
+// Results already in `path`. A missing file holds none, and a line cut off
+// by an interrupted run is dropped.
+pub fn read_results<P: AsRef<Path>>(path: P) -> io::Result<Vec<BatchResult>> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let lines: Vec<&str> = text.split_inclusive('\n').collect();
+    match lines.first() {
+        None => return Ok(Vec::new()),
+        Some(header) if header.trim_end() == HEADER => {}
+        Some(_) => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not a batch result file",
+            ))
+        }
+    }
+    let mut results = Vec::new();
+    for (line_nr, line) in lines.iter().enumerate().skip(1) {
+        let result = line
+            .strip_suffix('\n')
+            .and_then(|line| BatchResult::from_csv(line.trim_end_matches('\r')));
+        match result {
+            Some(result) => results.push(result),
+            // Only the last line can be unfinished
+            None if line_nr + 1 == lines.len() => {}
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: invalid {}", line_nr + 1, line.trim_end()),
+                ))
+            }
+        }
+    }
+    Ok(results)
+}
+
+// Replaces the file in one step, so an interrupted rewrite leaves the old
+// results in place
+fn write_results(path: &Path, results: &[BatchResult]) -> io::Result<()> {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    let mut writer = BufWriter::new(fs::File::create(&temp)?);
+    writeln!(writer, "{}", HEADER)?;
+    for result in results {
+        writeln!(writer, "{}", result.to_csv())?;
+    }
+    writer.flush()?;
+    writer.get_ref().sync_all()?;
+    drop(writer);
+    fs::rename(&temp, path)
+}
+
+// Solves every scramble without a solution in `output` on all cores,
+// appending each result as it is found. Returns the results of all scrambles
+// in order. Results found by another solver or in another metric are an
+// error, as are those of other scrambles.
+pub fn solve_batch<M: Policy + Sync>(
+    scrambles: &[String],
+    config: &SolverConfig<M>,
+    output: &Path,
+) -> io::Result<Vec<BatchResult>> {
+    let mut done: HashMap<usize, BatchResult> = HashMap::new();
+    for result in read_results(output)? {
+        if scrambles.get(result.index) != Some(&result.scramble) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} holds results of other scrambles", output.display()),
+            ));
+        }
+        if result.solver != config.solver {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} holds results of {}", output.display(), result.solver),
+            ));
+        }
+        if result.metric != config.metric() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} holds results in {}", output.display(), result.metric),
+            ));
+        }
+        // Try the unsolved ones again, e.g. with higher limits
+        if result.solution.is_some() {
+            done.insert(result.index, result);
+        }
+    }
+
+    // Start over from the complete lines, then append
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut kept: Vec<BatchResult> = done.values().cloned().collect();
+    kept.sort_by_key(|result| result.index);
+    write_results(output, &kept)?;
+    let writer = Mutex::new(fs::OpenOptions::new().append(true).open(output)?);
+
+    let new: Vec<BatchResult> = scrambles
+        .par_iter()
+        .enumerate()
+        .filter(|(index, _)| !done.contains_key(index))
+        .map(|(index, scramble)| {
+            let mut cube = RubiksCube::new();
+            cube.apply_scramble(scramble);
+            let start_time = Instant::now();
+            let result = config.solve(&cube);
+            let result = BatchResult {
+                index,
+                scramble: scramble.clone(),
+                nodes_expanded: result.as_ref().map_or(0, |r| r.nodes_expanded),
+                solution: result.map(|r| r.solution),
+                solver: config.solver,
+                metric: config.metric(),
+                time: start_time.elapsed(),
+            };
+            let mut file = writer.lock().unwrap_or_else(|e| e.into_inner());
+            writeln!(file, "{}", result.to_csv())?;
+            Ok(result)
+        })
+        .collect::<io::Result<_>>()?;
+
+    let mut results = kept;
+    results.extend(new);
+    results.sort_by_key(|result| result.index);
+    write_results(output, &results)?;
+    Ok(results)
+}
+
+// Aggregate statistics of a batch
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchSummary {
+    pub count: usize,
+    pub solved: usize,
+    pub mean_length: f64,
+    pub mean_nodes: f64,
+    pub mean_time: Duration,
+    // Summed over all scrambles, not the wall-clock time of the batch
+    pub total_time: Duration,
+    // Number of solutions of every length
+    pub lengths: BTreeMap<usize, usize>,
+}
+
+impl BatchSummary {
+    // Lengths (in the metric of each result), nodes and mean time are taken
+    // over the solved scrambles
+    pub fn new(results: &[BatchResult]) -> Self {
+        let solved: Vec<&BatchResult> = results.iter().filter(|r| r.solution.is_some()).collect();
+        let mut lengths = BTreeMap::new();
+        for result in &solved {
+            let length = result.length().unwrap_or(0);
+            *lengths.entry(length).or_insert(0) += 1;
+        }
+        let mean = |sum: f64| {
+            if solved.is_empty() {
+                0.0
+            } else {
+                sum / solved.len() as f64
+            }
+        };
+        let solved_time: Duration = solved.iter().map(|r| r.time).sum();
+        BatchSummary {
+            count: results.len(),
+            solved: solved.len(),
+            mean_length: mean(lengths.iter().map(|(&l, &n)| (l * n) as f64).sum()),
+            mean_nodes: mean(solved.iter().map(|r| r.nodes_expanded as f64).sum()),
+            mean_time: Duration::from_secs_f64(mean(solved_time.as_secs_f64())),
+            total_time: results.iter().map(|r| r.time).sum(),
+            lengths,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let lengths: Vec<String> = self
+            .lengths
+            .iter()
+            .map(|(length, count)| format!("\"{}\":{}", length, count))
+            .collect();
+        format!(
+            "{{\"count\":{},\"solved\":{},\"mean_length\":{},\"mean_nodes\":{},\"mean_time_ms\":{:.3},\"total_time_ms\":{:.3},\"lengths\":{{{}}}}}",
+            self.count,
+            self.solved,
+            self.mean_length,
+            self.mean_nodes,
+            self.mean_time.as_secs_f64() * 1000.0,
+            self.total_time.as_secs_f64() * 1000.0,
+            lengths.join(",")
+        )
+    }
+}
+
+impl fmt::Display for BatchSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Solved {}/{}", self.solved, self.count)?;
+        writeln!(
+            f,
+            "Mean length {:.2}, mean nodes {:.0}, mean time {:?}, total time {:?}",
+            self.mean_length, self.mean_nodes, self.mean_time, self.total_time
+        )?;
+        writeln!(f, "length  count")?;
+        for (length, count) in &self.lengths {
+            writeln!(f, "{:>6}  {}", length, count)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,188 @@
+//! Last layer cases for the OLL/PLL trainer.
+//!
+//! Every case comes with a common algorithm, rewritten in outer face turns
+//! where the usual version uses wide turns, slice moves or rotations. A case
+//! is set up by applying the inverse of its algorithm to a solved cube, so
+//! performing the algorithm (after the right U turn) solves the cube again.
+use super::cube::RubiksCube;
+use super::moves::{invert_algorithm, parse_algorithm, Face, Move, Turn};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CaseSet {
+    // Orientation of the last layer
+    Oll,
+    // Permutation of the last layer
+    Pll,
+}
+
+impl CaseSet {
+    pub const ALL: [CaseSet; 2] = [CaseSet::Oll, CaseSet::Pll];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CaseSet::Oll => "OLL",
+            CaseSet::Pll => "PLL",
+        }
+    }
+
+    pub fn cases(self) -> &'static [Case] {
+        match self {
+            CaseSet::Oll => &OLL_CASES,
+            CaseSet::Pll => &PLL_CASES,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Case {
+    pub set: CaseSet,
+    // Number of an OLL, letter of a PLL
+    pub name: &'static str,
+    pub algorithm: &'static str,
+}
+
+impl Case {
+    const fn oll(name: &'static str, algorithm: &'static str) -> Self {
+        Case {
+            set: CaseSet::Oll,
+            name,
+            algorithm,
+        }
+    }
+
+    const fn pll(name: &'static str, algorithm: &'static str) -> Self {
+        Case {
+            set: CaseSet::Pll,
+            name,
+            algorithm,
+        }
+    }
+
+    // "OLL 21", "T perm"
+    pub fn label(&self) -> String {
+        match self.set {
+            CaseSet::Oll => format!("OLL {}", self.name),
+            CaseSet::Pll => format!("{} perm", self.name),
+        }
+    }
+
+    pub fn moves(&self) -> Vec<Move> {
+        parse_algorithm(self.algorithm).expect("case algorithms are valid")
+    }
+
+    // The case on a solved cube. `pre_auf` and `post_auf` are quarter turns
+    // of U before and after the inverse algorithm, which show the case from
+    // another side and with the last layer turned.
+    pub fn setup(&self, pre_auf: usize, post_auf: usize) -> RubiksCube {
+        let mut cube = RubiksCube::new();
+        cube.apply_moves(&u_turns(pre_auf));
+        cube.apply_moves(&invert_algorithm(&self.moves()));
+        cube.apply_moves(&u_turns(post_auf));
+        cube
+    }
+}
+
+fn u_turns(quarter_turns: usize) -> Vec<Move> {
+    match quarter_turns % 4 {
+        1 => vec![Move::new(Face::U, Turn::Clockwise)],
+        2 => vec![Move::new(Face::U, Turn::Double)],
+        3 => vec![Move::new(Face::U, Turn::CounterClockwise)],
+        _ => Vec::new(),
+    }
+}
+
+// Whether the cube is solved or a single U turn away from it
+pub fn is_solved_up_to_auf(cube: &RubiksCube) -> bool {
+    (0..4).any(|quarter_turns| {
+        let mut cube = cube.clone();
+        cube.apply_moves(&u_turns(quarter_turns));
+        cube.is_solved()
+    })
+}
+
+pub const OLL_CASES: [Case; 57] = [
+    Case::oll("1", "R U2 R2 F R F' U2 R' F R F'"),
+    Case::oll("2", "F R U R' U' F' B U L U' L' B'"),
+    Case::oll("3", "B U L U' L' B' U' F R U R' U' F'"),
+    Case::oll("4", "B U L U' L' B' U F R U R' U' F'"),
+    Case::oll("5", "L' B2 R B R' B L"),
+    Case::oll("6", "L F2 R' F' R F' L'"),
+    Case::oll("7", "L F R' F R F2 L'"),
+    Case::oll("8", "R' F' L F' L' F2 R"),
+    Case::oll("9", "R U R' U' R' F R2 U R' U' F'"),
+    Case::oll("10", "R U R' U R' F R F' R U2 R'"),
+    Case::oll("11", "L F R' F R' D R D' R F2 L'"),
+    Case::oll("12", "R2 L F' R F' R' F2 R F' R L'"),
+    Case::oll("13", "F U R U' R2 F' R U R U' R'"),
+    Case::oll("14", "R' F R U R' F' R F U' F'"),
+    Case::oll("15", "R' F' R L' U' L U R' F R"),
+    Case::oll("16", "L F L' R U R' U' L F' L'"),
+    Case::oll("17", "R U R' U R' F R F' U2 R' F R F'"),
+    Case::oll("18", "L F R' F R F2 L2 B' R B' R' B2 L"),
+    Case::oll("19", "L' R B R B R' B' R2 L F R F'"),
+    Case::oll("20", "L F R' F' R2 L2 B R B' R' B' R' L"),
+    Case::oll("21", "R U2 R' U' R U R' U' R U' R'"),
+    Case::oll("22", "R U2 R2 U' R2 U' R2 U2 R"),
+    Case::oll("23", "R2 D' R U2 R' D R U2 R"),
+    Case::oll("24", "L F R' F' L' F R F'"),
+    Case::oll("25", "F' L F R' F' L' F R"),
+    Case::oll("26", "R U2 R' U' R U' R'"),
+    Case::oll("27", "R U R' U R U2 R'"),
+    Case::oll("28", "L F R' F' R L' U R U' R'"),
+    Case::oll("29", "R U R' U' R U' R' F' U' F R U R'"),
+    Case::oll("30", "F R' F R2 U' R' U' R U R' F2"),
+    Case::oll("31", "R' U' F U R U' R' F' R"),
+    Case::oll("32", "L U F' U' L' U L F L'"),
+    Case::oll("33", "R U R' U' R' F R F'"),
+    Case::oll("34", "R U R2 U' R' F R U R U' F'"),
+    Case::oll("35", "R U2 R2 F R F' R U2 R'"),
+    Case::oll("36", "L' U' L U' L' U L U L F' L' F"),
+    Case::oll("37", "F R' F' R U R U' R'"),
+    Case::oll("38", "R U R' U R U' R' U' R' F R F'"),
+    Case::oll("39", "L F' L' U' L U F U' L'"),
+    Case::oll("40", "R' F R U R' U' F' U R"),
+    Case::oll("41", "R U R' U R U2 R' F R U R' U' F'"),
+    Case::oll("42", "R' U' R U' R' U2 R F R U R' U' F'"),
+    Case::oll("43", "F' U' L' U L F"),
+    Case::oll("44", "F U R U' R' F'"),
+    Case::oll("45", "F R U R' U' F'"),
+    Case::oll("46", "R' U' R' F R F' U R"),
+    Case::oll("47", "R' U' R' F R F' R' F R F' U R"),
+    Case::oll("48", "F R U R' U' R U R' U' F'"),
+    Case::oll("49", "L F' L2 B L2 F L2 B' L"),
+    Case::oll("50", "L' B L2 F' L2 B' L2 F L'"),
+    Case::oll("51", "F U R U' R' U R U' R' F'"),
+    Case::oll("52", "R U R' U R U' B U' B' R'"),
+    Case::oll("53", "R' F2 L F L' F' L F L' F R"),
+    Case::oll("54", "L F2 R' F' R F R' F' R F' L'"),
+    Case::oll("55", "R' F R U R U' R2 F' R2 U' R' U R U R'"),
+    Case::oll("56", "L' B' L U' R' U R U' R' U R L' B L"),
+    Case::oll("57", "R U R' U' R' L F R F' L'"),
+];
+
+pub const PLL_CASES: [Case; 21] = [
+    Case::pll("Aa", "R' F R' B2 R F' R' B2 R2"),
+    Case::pll("Ab", "R2 B2 R F R' B2 R F' R"),
+    Case::pll("E", "R B' R' F R B R' F' R B R' F R B' R' F'"),
+    Case::pll("F", "R' U' F' R U R' U' R' F R2 U' R' U' R U R' U R"),
+    Case::pll("Ga", "R2 U R' U R' U' R U' R2 U' D R' U R D'"),
+    Case::pll("Gb", "R' U' R U D' R2 U R' U R U' R U' R2 D"),
+    Case::pll("Gc", "R2 U' R U' R U R' U R2 U D' R U' R' D"),
+    Case::pll("Gd", "R U R' U' D R2 U' R U' R' U R' U R2 D'"),
+    Case::pll("H", "R2 L2 D R2 L2 U2 R2 L2 D R2 L2"),
+    Case::pll("Ja", "L' U' L F L' U' L U L F' L2 U L"),
+    Case::pll("Jb", "R U R' F' R U R' U' R' F R2 U' R'"),
+    Case::pll(
+        "Na",
+        "R U R' U R U R' F' R U R' U' R' F R2 U' R' U2 R U' R'",
+    ),
+    Case::pll("Nb", "R' U R U' R' F' U' F R U R' F R' F' R U' R"),
+    Case::pll("Ra", "R U' R' U' R U R D R' U' R D' R' U2 R'"),
+    Case::pll("Rb", "R2 F R U R U' R' F' R U2 R' U2 R"),
+    Case::pll("T", "R U R' U' R' F R2 U' R' U' R U R' F'"),
+    Case::pll("Ua", "R U' R U R U R U' R' U' R2"),
+    Case::pll("Ub", "R2 U R U R' U' R' U' R' U R'"),
+    Case::pll("V", "R' U R' U' B' R' B2 U' B' U B' R B R"),
+    Case::pll("Y", "F R U' R' U' R U R' F' R U R' U' R' F R F'"),
+    Case::pll("Z", "R' L F R2 L2 B R2 L2 F R' L D2 R2 L2"),
+];
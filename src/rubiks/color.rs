@@ -1,7 +1,6 @@
-use eframe::egui::Color32;
-
 // Defining colors of the stickers
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Color {
     W, // White
     Y, // Yellow
@@ -12,14 +11,5 @@ pub enum Color {
 }
 
 impl Color {
-    pub fn to_color32(&self) -> Color32 {
-        match self {
-            Color::W => Color32::WHITE,
-            Color::Y => Color32::from_rgb(255, 255, 0),
-            Color::G => Color32::from_rgb(0, 255, 0),
-            Color::B => Color32::from_rgb(0, 0, 255),
-            Color::R => Color32::from_rgb(255, 0, 0),
-            Color::O => Color32::from_rgb(255, 165, 0),
-        }
-    }
+    pub const ALL: [Color; 6] = [Color::W, Color::Y, Color::G, Color::B, Color::R, Color::O];
 }
@@ -2,12 +2,22 @@ use super::color::Color;
 use crate::ui::app::MyApp;
 use eframe::NativeOptions;
 
+// Signature shared by all the face turns below
+pub type MoveFn = fn(&mut RubiksCube);
+
 // Define main data structure for the cube
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RubiksCube {
     pub faces: [[Color; 9]; 6], // 6 faces, each with 9 stickers
 }
 
+impl Default for RubiksCube {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl RubiksCube {
     // Create new cube in the solved state
     pub fn new() -> Self {
@@ -23,6 +33,7 @@ impl RubiksCube {
         }
     }
 
+    #[allow(clippy::inherent_to_string_shadow_display)]
     pub fn to_string(&self) -> String {
         self.faces
             .iter()
@@ -58,7 +69,7 @@ impl RubiksCube {
        input_vector
    }
 
-    pub fn all_moves(&mut self) -> Vec<(&'static str, fn(&mut Self))> {
+    pub fn all_moves(&mut self) -> Vec<(&'static str, MoveFn)> {
         vec![
             ("U", RubiksCube::u_clockwise),
             ("U'", RubiksCube::u_counterclockwise),
@@ -137,7 +148,7 @@ impl RubiksCube {
     // Apply one of the 12 possible moves to the cube by
     // changing positions of single stickers
     pub fn u_clockwise(&mut self) {
-        let temp = self.faces.clone();
+        let temp = self.faces;
         self.faces[0][0] = temp[0][6];
         self.faces[0][1] = temp[0][3];
         self.faces[0][2] = temp[0][0];
@@ -161,7 +172,7 @@ impl RubiksCube {
     }
 
     pub fn u_counterclockwise(&mut self) {
-        let temp = self.faces.clone();
+        let temp = self.faces;
         self.faces[0][0] = temp[0][2];
         self.faces[0][1] = temp[0][5];
         self.faces[0][2] = temp[0][8];
@@ -185,7 +196,7 @@ impl RubiksCube {
     }
 
     pub fn d_clockwise(&mut self) {
-        let temp = self.faces.clone();
+        let temp = self.faces;
         self.faces[1][0] = temp[1][6];
         self.faces[1][1] = temp[1][3];
         self.faces[1][2] = temp[1][0];
@@ -209,7 +220,7 @@ impl RubiksCube {
     }
 
     pub fn d_counterclockwise(&mut self) {
-        let temp = self.faces.clone();
+        let temp = self.faces;
         self.faces[1][0] = temp[1][2];
         self.faces[1][1] = temp[1][5];
         self.faces[1][2] = temp[1][8];
@@ -233,7 +244,7 @@ impl RubiksCube {
     }
 
     pub fn f_clockwise(&mut self) {
-        let temp = self.faces.clone();
+        let temp = self.faces;
         self.faces[2][0] = temp[2][6];
         self.faces[2][1] = temp[2][3];
         self.faces[2][2] = temp[2][0];
@@ -257,7 +268,7 @@ impl RubiksCube {
     }
 
     pub fn f_counterclockwise(&mut self) {
-        let temp = self.faces.clone();
+        let temp = self.faces;
         self.faces[2][0] = temp[2][2];
         self.faces[2][1] = temp[2][5];
         self.faces[2][2] = temp[2][8];
@@ -281,7 +292,7 @@ impl RubiksCube {
     }
 
     pub fn b_clockwise(&mut self) {
-        let temp = self.faces.clone();
+        let temp = self.faces;
         self.faces[3][0] = temp[3][6];
         self.faces[3][1] = temp[3][3];
         self.faces[3][2] = temp[3][0];
@@ -305,7 +316,7 @@ impl RubiksCube {
     }
 
     pub fn b_counterclockwise(&mut self) {
-        let temp = self.faces.clone();
+        let temp = self.faces;
         self.faces[3][0] = temp[3][2];
         self.faces[3][1] = temp[3][5];
         self.faces[3][2] = temp[3][8];
@@ -329,7 +340,7 @@ impl RubiksCube {
     }
 
     pub fn r_clockwise(&mut self) {
-        let temp = self.faces.clone();
+        let temp = self.faces;
         self.faces[4][0] = temp[4][6];
         self.faces[4][1] = temp[4][3];
         self.faces[4][2] = temp[4][0];
@@ -353,7 +364,7 @@ impl RubiksCube {
     }
 
     pub fn r_counterclockwise(&mut self) {
-        let temp = self.faces.clone();
+        let temp = self.faces;
         self.faces[4][0] = temp[4][2];
         self.faces[4][1] = temp[4][5];
         self.faces[4][2] = temp[4][8];
@@ -377,7 +388,7 @@ impl RubiksCube {
     }
 
     pub fn l_clockwise(&mut self) {
-        let temp = self.faces.clone();
+        let temp = self.faces;
         self.faces[5][0] = temp[5][6];
         self.faces[5][1] = temp[5][3];
         self.faces[5][2] = temp[5][0];
@@ -401,7 +412,7 @@ impl RubiksCube {
     }
 
     pub fn l_counterclockwise(&mut self) {
-        let temp = self.faces.clone();
+        let temp = self.faces;
         self.faces[5][0] = temp[5][2];
         self.faces[5][1] = temp[5][5];
         self.faces[5][2] = temp[5][8];
@@ -425,12 +436,20 @@ impl RubiksCube {
     }
 
     pub fn visualize(self) {
+        self.visualize_with(false);
+    }
+
+    // With `keep_cube` the window shows this cube instead of the one of the
+    // saved session
+    pub fn visualize_with(self, keep_cube: bool) {
         let options = NativeOptions::default();
         let cube_clone = self.clone();
         let _ = eframe::run_native(
             "Rubik's Cube Visualizer",
             options,
-            Box::new(|_cc| Ok(Box::new(MyApp::new(cube_clone)))),
+            Box::new(move |cc| {
+                Ok(Box::new(MyApp::restore_with(cube_clone, cc.storage, keep_cube)))
+            }),
         );
     }
 }
@@ -0,0 +1,417 @@
+//! Cubie representation of the cube: which corner and edge sits in every
+//! position and how it is twisted or flipped.
+//!
+//! Pieces are numbered in the usual (Kociemba) order. The first facelet of a
+//! corner is its U or D sticker and the others follow clockwise, and a corner
+//! has orientation k when its U or D sticker is on the k-th facelet of its
+//! position. An edge has orientation 1 when it sits flipped, i.e. its first
+//! sticker is on the second facelet of its position.
+use super::color::Color;
+use super::cube::RubiksCube;
+use super::moves::Move;
+use std::fmt;
+
+const U: usize = 0;
+const D: usize = 1;
+const F: usize = 2;
+const B: usize = 3;
+const R: usize = 4;
+const L: usize = 5;
+
+// (face, index) of the facelets of every corner position
+pub const CORNER_FACELETS: [[(usize, usize); 3]; 8] = [
+    [(U, 8), (R, 0), (F, 2)], // URF
+    [(U, 6), (F, 0), (L, 2)], // UFL
+    [(U, 0), (L, 0), (B, 2)], // ULB
+    [(U, 2), (B, 0), (R, 2)], // UBR
+    [(D, 2), (F, 8), (R, 6)], // DFR
+    [(D, 0), (L, 8), (F, 6)], // DLF
+    [(D, 6), (B, 8), (L, 6)], // DBL
+    [(D, 8), (R, 8), (B, 6)], // DRB
+];
+
+// (face, index) of the facelets of every edge position
+pub const EDGE_FACELETS: [[(usize, usize); 2]; 12] = [
+    [(U, 5), (R, 1)], // UR
+    [(U, 7), (F, 1)], // UF
+    [(U, 3), (L, 1)], // UL
+    [(U, 1), (B, 1)], // UB
+    [(D, 5), (R, 7)], // DR
+    [(D, 1), (F, 7)], // DF
+    [(D, 3), (L, 7)], // DL
+    [(D, 7), (B, 7)], // DB
+    [(F, 5), (R, 3)], // FR
+    [(F, 3), (L, 5)], // FL
+    [(B, 5), (L, 3)], // BL
+    [(B, 3), (R, 5)], // BR
+];
+
+pub const CORNER_NAMES: [&str; 8] = ["URF", "UFL", "ULB", "UBR", "DFR", "DLF", "DBL", "DRB"];
+pub const EDGE_NAMES: [&str; 12] = [
+    "UR", "UF", "UL", "UB", "DR", "DF", "DL", "DB", "FR", "FL", "BL", "BR",
+];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "RawCubieCube")
+)]
+pub struct CubieCube {
+    // Corner in every corner position
+    pub corner_perm: [u8; 8],
+    pub corner_orient: [u8; 8],
+    // Edge in every edge position
+    pub edge_perm: [u8; 12],
+    pub edge_orient: [u8; 12],
+}
+
+// Deserialized pieces, only turned into a CubieCube once they are checked
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawCubieCube {
+    corner_perm: [u8; 8],
+    corner_orient: [u8; 8],
+    edge_perm: [u8; 12],
+    edge_orient: [u8; 12],
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawCubieCube> for CubieCube {
+    type Error = String;
+
+    fn try_from(raw: RawCubieCube) -> Result<Self, String> {
+        let cubie = CubieCube {
+            corner_perm: raw.corner_perm,
+            corner_orient: raw.corner_orient,
+            edge_perm: raw.edge_perm,
+            edge_orient: raw.edge_orient,
+        };
+        cubie.verify().map(|()| cubie).map_err(|errors| {
+            let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            errors.join(", ")
+        })
+    }
+}
+
+// Why a sticker assignment is not a state a real cube can be turned into
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StateError {
+    // Every colour has to show up on exactly nine stickers
+    ColorCount { color: Color, count: usize },
+    // The six centres need six different colours
+    DuplicateCenters,
+    // The stickers of a position don't make up any piece
+    ImpossibleCorner { position: usize },
+    ImpossibleEdge { position: usize },
+    // A position holds a piece number or orientation out of range
+    InvalidCorner { position: usize },
+    InvalidEdge { position: usize },
+    // A piece shows up in more than one position
+    DuplicateCorner { corner: usize },
+    DuplicateEdge { edge: usize },
+    // The pieces are all there, but no sequence of turns reaches the state
+    CornerTwist,
+    EdgeFlip,
+    Parity,
+}
+
+impl fmt::Display for StateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StateError::ColorCount { color, count } => {
+                write!(f, "{:?} is on {} stickers instead of 9", color, count)
+            }
+            StateError::DuplicateCenters => write!(f, "two centres have the same colour"),
+            StateError::ImpossibleCorner { position } => write!(
+                f,
+                "the stickers at {} don't belong to any corner",
+                CORNER_NAMES[*position]
+            ),
+            StateError::ImpossibleEdge { position } => write!(
+                f,
+                "the stickers at {} don't belong to any edge",
+                EDGE_NAMES[*position]
+            ),
+            StateError::InvalidCorner { position } => {
+                write!(f, "no corner can be at {}", CORNER_NAMES[*position])
+            }
+            StateError::InvalidEdge { position } => {
+                write!(f, "no edge can be at {}", EDGE_NAMES[*position])
+            }
+            StateError::DuplicateCorner { corner } => {
+                write!(f, "corner {} appears more than once", CORNER_NAMES[*corner])
+            }
+            StateError::DuplicateEdge { edge } => {
+                write!(f, "edge {} appears more than once", EDGE_NAMES[*edge])
+            }
+            StateError::CornerTwist => write!(f, "a corner is twisted"),
+            StateError::EdgeFlip => write!(f, "an edge is flipped"),
+            StateError::Parity => write!(f, "two pieces are swapped"),
+        }
+    }
+}
+
+impl Default for CubieCube {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Whether a permutation needs an odd number of swaps
+fn is_odd(perm: &[u8]) -> bool {
+    let inversions = (0..perm.len())
+        .flat_map(|i| (i + 1..perm.len()).map(move |j| (i, j)))
+        .filter(|&(i, j)| perm[i] > perm[j])
+        .count();
+    inversions % 2 == 1
+}
+
+impl CubieCube {
+    // The solved cube
+    pub fn new() -> Self {
+        CubieCube {
+            corner_perm: [0, 1, 2, 3, 4, 5, 6, 7],
+            corner_orient: [0; 8],
+            edge_perm: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+            edge_orient: [0; 12],
+        }
+    }
+
+    // Reads the pieces off the stickers. Colours are matched to faces by the
+    // centres, and every problem found is reported.
+    pub fn from_facelets(cube: &RubiksCube) -> Result<Self, Vec<StateError>> {
+        let mut errors: Vec<StateError> = Color::ALL
+            .iter()
+            .map(|&color| {
+                let count = cube.faces.iter().flatten().filter(|&&c| c == color).count();
+                StateError::ColorCount { color, count }
+            })
+            .filter(|e| matches!(e, StateError::ColorCount { count, .. } if *count != 9))
+            .collect();
+
+        let centers: Vec<Color> = cube.faces.iter().map(|face| face[4]).collect();
+        if (0..6).any(|i| centers[i + 1..].contains(&centers[i])) {
+            errors.push(StateError::DuplicateCenters);
+            return Err(errors);
+        }
+        let face_of = |(face, index): (usize, usize)| {
+            centers
+                .iter()
+                .position(|&c| c == cube.faces[face][index])
+                .expect("every colour is on a centre")
+        };
+
+        let mut cubie = CubieCube::new();
+        for (position, facelets) in CORNER_FACELETS.iter().enumerate() {
+            let faces = facelets.map(face_of);
+            let piece = faces
+                .iter()
+                .position(|&face| face == U || face == D)
+                .and_then(|orient| {
+                    let turned = [0, 1, 2].map(|k| faces[(orient + k) % 3]);
+                    CORNER_FACELETS
+                        .iter()
+                        .position(|home| home.map(|(face, _)| face) == turned)
+                        .map(|corner| (corner, orient))
+                });
+            match piece {
+                Some((corner, orient)) => {
+                    cubie.corner_perm[position] = corner as u8;
+                    cubie.corner_orient[position] = orient as u8;
+                }
+                None => errors.push(StateError::ImpossibleCorner { position }),
+            }
+        }
+        for (position, facelets) in EDGE_FACELETS.iter().enumerate() {
+            let faces = facelets.map(face_of);
+            let piece = EDGE_FACELETS.iter().enumerate().find_map(|(edge, home)| {
+                let home = home.map(|(face, _)| face);
+                if home == faces {
+                    Some((edge, 0))
+                } else if home == [faces[1], faces[0]] {
+                    Some((edge, 1))
+                } else {
+                    None
+                }
+            });
+            match piece {
+                Some((edge, orient)) => {
+                    cubie.edge_perm[position] = edge as u8;
+                    cubie.edge_orient[position] = orient;
+                }
+                None => errors.push(StateError::ImpossibleEdge { position }),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        cubie.verify().map(|()| cubie)
+    }
+
+    // Checks that the pieces make up a state the solved cube can be turned
+    // into: every piece once, orientations in range and summing up, and the
+    // same parity for corners and edges
+    pub fn verify(&self) -> Result<(), Vec<StateError>> {
+        let mut errors: Vec<StateError> = (0..8)
+            .filter(|&position| {
+                self.corner_perm[position] >= 8 || self.corner_orient[position] >= 3
+            })
+            .map(|position| StateError::InvalidCorner { position })
+            .collect();
+        errors.extend(
+            (0..12)
+                .filter(|&position| {
+                    self.edge_perm[position] >= 12 || self.edge_orient[position] >= 2
+                })
+                .map(|position| StateError::InvalidEdge { position }),
+        );
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        errors.extend(
+            (0..8)
+                .filter(|&corner| self.corner_perm.iter().filter(|&&c| c == corner).count() > 1)
+                .map(|corner| StateError::DuplicateCorner {
+                    corner: corner as usize,
+                }),
+        );
+        errors.extend(
+            (0..12)
+                .filter(|&edge| self.edge_perm.iter().filter(|&&e| e == edge).count() > 1)
+                .map(|edge| StateError::DuplicateEdge {
+                    edge: edge as usize,
+                }),
+        );
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        if self
+            .corner_orient
+            .iter()
+            .map(|&o| o as usize)
+            .sum::<usize>()
+            % 3
+            != 0
+        {
+            errors.push(StateError::CornerTwist);
+        }
+        if self.edge_orient.iter().map(|&o| o as usize).sum::<usize>() % 2 != 0 {
+            errors.push(StateError::EdgeFlip);
+        }
+        if is_odd(&self.corner_perm) != is_odd(&self.edge_perm) {
+            errors.push(StateError::Parity);
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    // The stickers of this state, with the centres of the solved cube
+    pub fn to_facelets(&self) -> RubiksCube {
+        let solved = RubiksCube::new();
+        let color_of = |face: usize| solved.faces[face][4];
+        let mut cube = RubiksCube::new();
+        for (position, facelets) in CORNER_FACELETS.iter().enumerate() {
+            let home = CORNER_FACELETS[self.corner_perm[position] as usize];
+            let orient = self.corner_orient[position] as usize;
+            for k in 0..3 {
+                let (face, index) = facelets[(k + orient) % 3];
+                cube.faces[face][index] = color_of(home[k].0);
+            }
+        }
+        for (position, facelets) in EDGE_FACELETS.iter().enumerate() {
+            let home = EDGE_FACELETS[self.edge_perm[position] as usize];
+            let orient = self.edge_orient[position] as usize;
+            for k in 0..2 {
+                let (face, index) = facelets[(k + orient) % 2];
+                cube.faces[face][index] = color_of(home[k].0);
+            }
+        }
+        cube
+    }
+
+    // The state after `self` followed by `other`
+    pub fn multiply(&self, other: &CubieCube) -> CubieCube {
+        let mut product = CubieCube::new();
+        for i in 0..8 {
+            let from = other.corner_perm[i] as usize;
+            product.corner_perm[i] = self.corner_perm[from];
+            product.corner_orient[i] = (self.corner_orient[from] + other.corner_orient[i]) % 3;
+        }
+        for i in 0..12 {
+            let from = other.edge_perm[i] as usize;
+            product.edge_perm[i] = self.edge_perm[from];
+            product.edge_orient[i] = (self.edge_orient[from] + other.edge_orient[i]) % 2;
+        }
+        product
+    }
+
+    // Turns the pieces directly, independently of the sticker tables of
+    // RubiksCube
+    pub fn apply_move(&mut self, m: Move) {
+        let turn = &FACE_TURNS[m.face.index()];
+        for _ in 0..m.turn.quarter_turns() {
+            *self = self.multiply(turn);
+        }
+    }
+}
+
+// Clockwise quarter turn of every face, indexed like Face::ALL, as the piece
+// that ends up in every position (Kociemba's definition)
+const FACE_TURNS: [CubieCube; 6] = [
+    // U
+    CubieCube {
+        corner_perm: [3, 0, 1, 2, 4, 5, 6, 7],
+        corner_orient: [0; 8],
+        edge_perm: [3, 0, 1, 2, 4, 5, 6, 7, 8, 9, 10, 11],
+        edge_orient: [0; 12],
+    },
+    // D
+    CubieCube {
+        corner_perm: [0, 1, 2, 3, 5, 6, 7, 4],
+        corner_orient: [0; 8],
+        edge_perm: [0, 1, 2, 3, 5, 6, 7, 4, 8, 9, 10, 11],
+        edge_orient: [0; 12],
+    },
+    // F
+    CubieCube {
+        corner_perm: [1, 5, 2, 3, 0, 4, 6, 7],
+        corner_orient: [1, 2, 0, 0, 2, 1, 0, 0],
+        edge_perm: [0, 9, 2, 3, 4, 8, 6, 7, 1, 5, 10, 11],
+        edge_orient: [0, 1, 0, 0, 0, 1, 0, 0, 1, 1, 0, 0],
+    },
+    // B
+    CubieCube {
+        corner_perm: [0, 1, 3, 7, 4, 5, 2, 6],
+        corner_orient: [0, 0, 1, 2, 0, 0, 2, 1],
+        edge_perm: [0, 1, 2, 11, 4, 5, 6, 10, 8, 9, 3, 7],
+        edge_orient: [0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 1, 1],
+    },
+    // R
+    CubieCube {
+        corner_perm: [4, 1, 2, 0, 7, 5, 6, 3],
+        corner_orient: [2, 0, 0, 1, 1, 0, 0, 2],
+        edge_perm: [8, 1, 2, 3, 11, 5, 6, 7, 4, 9, 10, 0],
+        edge_orient: [0; 12],
+    },
+    // L
+    CubieCube {
+        corner_perm: [0, 2, 6, 3, 4, 1, 5, 7],
+        corner_orient: [0, 1, 2, 0, 0, 2, 1, 0],
+        edge_perm: [0, 1, 10, 3, 4, 5, 9, 7, 8, 2, 6, 11],
+        edge_orient: [0; 12],
+    },
+];
+
+impl RubiksCube {
+    // Checks that the stickers describe a state reachable by turning faces
+    pub fn validate(&self) -> Result<CubieCube, Vec<StateError>> {
+        CubieCube::from_facelets(self)
+    }
+}
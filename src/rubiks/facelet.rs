@@ -0,0 +1,116 @@
+//! Text formats for cube states.
+//!
+//! The facelet string is the 54 character format of Kociemba's solver and
+//! most other solvers: the faces in the order U R F D L B, each as its nine
+//! stickers in reading order, with every sticker written as the face whose
+//! centre has its colour. The stickers of a face are numbered like in
+//! `RubiksCube::faces`, so only the order of the faces differs:
+//!
+//! ```text
+//!              |************|
+//!              |*U1**U2**U3*|
+//!              |*U4**U5**U6*|
+//!              |*U7**U8**U9*|
+//! |************|************|************|************|
+//! |*L1**L2**L3*|*F1**F2**F3*|*R1**R2**R3*|*B1**B2**B3*|
+//! |*L4**L5**L6*|*F4**F5**F6*|*R4**R5**R6*|*B4**B5**B6*|
+//! |*L7**L8**L9*|*F7**F8**F9*|*R7**R8**R9*|*B7**B8**B9*|
+//! |************|************|************|************|
+//!              |*D1**D2**D3*|
+//!              |*D4**D5**D6*|
+//!              |*D7**D8**D9*|
+//! ```
+//!
+//! The state string is the crate's own format written by
+//! `RubiksCube::to_string`: the faces in the order U D F B R L with the
+//! colour letters W Y G B R O.
+use super::color::Color;
+use super::cube::RubiksCube;
+use super::moves::Face;
+
+// Order of the faces in a facelet string
+pub const FACELET_ORDER: [Face; 6] = [Face::U, Face::R, Face::F, Face::D, Face::L, Face::B];
+
+impl RubiksCube {
+    // Reads a facelet string. The symbol on the centre of a face stands for
+    // that face, so besides URFDLB any six distinct symbols are accepted,
+    // e.g. the colours the stickers have in whatever way the cube is held.
+    // Every face gets the colour it has on the solved cube.
+    pub fn from_facelet_string(facelets: &str) -> Result<Self, String> {
+        let facelets: Vec<char> = facelets.trim().chars().collect();
+        if facelets.len() != 54 {
+            return Err(format!(
+                "a facelet string has 54 stickers, not {}",
+                facelets.len()
+            ));
+        }
+        let centers: Vec<char> = (0..6).map(|k| facelets[9 * k + 4]).collect();
+        if (1..6).any(|k| centers[..k].contains(&centers[k])) {
+            return Err("the six centres need six different symbols".to_string());
+        }
+
+        let solved = RubiksCube::new();
+        let mut cube = RubiksCube::new();
+        for (i, symbol) in facelets.iter().enumerate() {
+            let face = centers
+                .iter()
+                .position(|c| c == symbol)
+                .map(|k| FACELET_ORDER[k])
+                .ok_or_else(|| format!("sticker {} ({}) matches no centre", i + 1, symbol))?;
+            cube.faces[FACELET_ORDER[i / 9].index()][i % 9] = solved.faces[face.index()][4];
+        }
+        for (k, symbol) in centers.iter().enumerate() {
+            let count = facelets.iter().filter(|c| *c == symbol).count();
+            if count != 9 {
+                return Err(format!(
+                    "{} stickers of face {} instead of 9",
+                    count,
+                    FACELET_ORDER[k].letter()
+                ));
+            }
+        }
+        Ok(cube)
+    }
+
+    // Writes the facelet string, naming every sticker after the face whose
+    // centre has its colour. Fails if two centres have the same colour.
+    pub fn to_facelet_string(&self) -> Result<String, String> {
+        let center_of = |color: Color| {
+            let mut faces = Face::ALL
+                .into_iter()
+                .filter(|face| self.faces[face.index()][4] == color);
+            match (faces.next(), faces.next()) {
+                (Some(face), None) => Ok(face),
+                (None, _) => Err(format!("no centre is {:?}", color)),
+                (Some(_), Some(_)) => Err(format!("two centres are {:?}", color)),
+            }
+        };
+        FACELET_ORDER
+            .iter()
+            .flat_map(|face| self.faces[face.index()].iter())
+            .map(|&color| center_of(color).map(Face::letter))
+            .collect()
+    }
+
+    // Inverse of RubiksCube::to_string
+    pub fn from_state_string(state: &str) -> Result<Self, String> {
+        let state = state.trim();
+        let stickers = state.chars().count();
+        if stickers != 54 {
+            return Err(format!("a state has 54 stickers, not {}", stickers));
+        }
+        let mut cube = RubiksCube::new();
+        for (i, c) in state.chars().enumerate() {
+            cube.faces[i / 9][i % 9] = match c {
+                'W' => Color::W,
+                'Y' => Color::Y,
+                'G' => Color::G,
+                'B' => Color::B,
+                'R' => Color::R,
+                'O' => Color::O,
+                _ => return Err(format!("sticker {} ({}) is no colour", i + 1, c)),
+            };
+        }
+        Ok(cube)
+    }
+}
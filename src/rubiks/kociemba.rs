@@ -0,0 +1,452 @@
+//! Kociemba's two-phase algorithm.
+//!
+//! Phase 1 brings the cube into the group G1 = <U, D, R2, L2, F2, B2>: no
+//! corner twisted, no edge flipped and the four edges of the middle (UD)
+//! slice somewhere in that slice. Phase 2 then solves it with the moves of
+//! G1 only. Each phase is an IDA* search over coordinates, numbers that
+//! describe one aspect of the state:
+//!
+//! - phase 1: corner twist (3^7), edge flip (2^11) and which four positions
+//!   hold the slice edges (12 choose 4)
+//! - phase 2: permutation of the corners (8!), of the U and D edges (8!) and
+//!   of the slice edges (4!)
+//!
+//! Move tables turn the coordinates without touching a cube, and pruning
+//! tables hold the exact distance of every pair (slice, other coordinate),
+//! which is the heuristic. Solutions are short, usually about 20 to 25 face
+//! turns for a random state, but not optimal: phase 1 is tried at
+//! increasing lengths, and the first solution of at most `max_length` moves
+//! in total is returned.
+use super::cube::RubiksCube;
+use super::cubie::CubieCube;
+use super::moves::{Face, Move, Turn};
+use super::solver::SearchResult;
+
+const TWISTS: usize = 2187;
+const FLIPS: usize = 2048;
+const SLICES: usize = 495;
+const CORNER_PERMS: usize = 40320;
+const EDGE_PERMS: usize = 40320;
+const SLICE_PERMS: usize = 24;
+
+// Distance of an entry a pruning table hasn't reached yet
+const UNSEEN: u8 = u8::MAX;
+
+// The moves of G1
+const PHASE2_MOVES: [Move; 10] = [
+    Move::new(Face::U, Turn::Clockwise),
+    Move::new(Face::U, Turn::Double),
+    Move::new(Face::U, Turn::CounterClockwise),
+    Move::new(Face::D, Turn::Clockwise),
+    Move::new(Face::D, Turn::Double),
+    Move::new(Face::D, Turn::CounterClockwise),
+    Move::new(Face::F, Turn::Double),
+    Move::new(Face::B, Turn::Double),
+    Move::new(Face::R, Turn::Double),
+    Move::new(Face::L, Turn::Double),
+];
+
+fn in_phase2(m: Move) -> bool {
+    matches!(m.face, Face::U | Face::D) || m.turn == Turn::Double
+}
+
+// Whether `m` may follow `last` in a search: no face twice in a row, and
+// of two opposite faces the first in Face::ALL first
+fn may_follow(last: Option<&Move>, m: Move) -> bool {
+    match last {
+        None => true,
+        Some(last) => {
+            m.face != last.face
+                && !(m.face == last.face.opposite() && m.face.index() < last.face.index())
+        }
+    }
+}
+
+// Rank of a permutation of 0..n among all of them in lexicographic order
+fn perm_rank(perm: &[u8]) -> usize {
+    (0..perm.len()).fold(0, |rank, i| {
+        let smaller = perm[i + 1..].iter().filter(|&&p| p < perm[i]).count();
+        rank * (perm.len() - i) + smaller
+    })
+}
+
+fn perm_unrank(mut rank: usize, n: usize) -> Vec<u8> {
+    let mut digits = vec![0; n];
+    for i in (0..n).rev() {
+        digits[i] = rank % (n - i);
+        rank /= n - i;
+    }
+    let mut left: Vec<u8> = (0..n as u8).collect();
+    digits.iter().map(|&d| left.remove(d)).collect()
+}
+
+fn twist(cube: &CubieCube) -> usize {
+    cube.corner_orient[..7]
+        .iter()
+        .fold(0, |twist, &o| 3 * twist + o as usize)
+}
+
+// The last corner is twisted so that the twists add up
+fn with_twist(mut twist: usize) -> CubieCube {
+    let mut cube = CubieCube::new();
+    for i in (0..7).rev() {
+        cube.corner_orient[i] = (twist % 3) as u8;
+        twist /= 3;
+    }
+    let sum: u8 = cube.corner_orient[..7].iter().sum();
+    cube.corner_orient[7] = (3 - sum % 3) % 3;
+    cube
+}
+
+fn flip(cube: &CubieCube) -> usize {
+    cube.edge_orient[..11]
+        .iter()
+        .fold(0, |flip, &o| 2 * flip + o as usize)
+}
+
+fn with_flip(mut flip: usize) -> CubieCube {
+    let mut cube = CubieCube::new();
+    for i in (0..11).rev() {
+        cube.edge_orient[i] = (flip % 2) as u8;
+        flip /= 2;
+    }
+    let sum: u8 = cube.edge_orient[..11].iter().sum();
+    cube.edge_orient[11] = sum % 2;
+    cube
+}
+
+// Positions of the slice edges (FR, FL, BL, BR) as a set of bits
+fn slice_mask(cube: &CubieCube) -> usize {
+    (0..12)
+        .filter(|&i| cube.edge_perm[i] >= 8)
+        .fold(0, |mask, i| mask | 1 << i)
+}
+
+// The slice edges in the positions of `mask`, the others around them
+fn with_slice_mask(mask: usize) -> CubieCube {
+    let mut cube = CubieCube::new();
+    let (mut slice, mut other) = (8, 0);
+    for i in 0..12 {
+        if mask & 1 << i != 0 {
+            cube.edge_perm[i] = slice;
+            slice += 1;
+        } else {
+            cube.edge_perm[i] = other;
+            other += 1;
+        }
+    }
+    cube
+}
+
+fn corner_perm(cube: &CubieCube) -> usize {
+    perm_rank(&cube.corner_perm)
+}
+
+fn with_corner_perm(rank: usize) -> CubieCube {
+    let mut cube = CubieCube::new();
+    cube.corner_perm.copy_from_slice(&perm_unrank(rank, 8));
+    cube
+}
+
+// Only a coordinate in G1, where the U and D edges stay in their layers
+fn edge_perm(cube: &CubieCube) -> usize {
+    perm_rank(&cube.edge_perm[..8])
+}
+
+fn with_edge_perm(rank: usize) -> CubieCube {
+    let mut cube = CubieCube::new();
+    cube.edge_perm[..8].copy_from_slice(&perm_unrank(rank, 8));
+    cube
+}
+
+fn slice_perm(cube: &CubieCube) -> usize {
+    let slice: Vec<u8> = cube.edge_perm[8..].iter().map(|e| e - 8).collect();
+    perm_rank(&slice)
+}
+
+fn with_slice_perm(rank: usize) -> CubieCube {
+    let mut cube = CubieCube::new();
+    for (i, e) in perm_unrank(rank, 4).into_iter().enumerate() {
+        cube.edge_perm[8 + i] = e + 8;
+    }
+    cube
+}
+
+// The coordinate after every move, for every value of the coordinate
+fn move_table(
+    size: usize,
+    moves: &[Move],
+    with: impl Fn(usize) -> CubieCube,
+    coordinate: impl Fn(&CubieCube) -> usize,
+) -> Vec<Vec<u16>> {
+    (0..size)
+        .map(|value| {
+            let cube = with(value);
+            moves
+                .iter()
+                .map(|&m| {
+                    let mut next = cube;
+                    next.apply_move(m);
+                    coordinate(&next) as u16
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// Distance of every pair of coordinates from the pair of the solved cube,
+// by a BFS over the move tables of both. Entry (a, b) is at a * size of b + b.
+fn pruning_table(a_moves: &[Vec<u16>], b_moves: &[Vec<u16>], solved: (usize, usize)) -> Vec<u8> {
+    let b_size = b_moves.len();
+    let mut distances = vec![UNSEEN; a_moves.len() * b_size];
+    distances[solved.0 * b_size + solved.1] = 0;
+    let mut frontier = vec![solved.0 * b_size + solved.1];
+    let mut depth = 0;
+    while !frontier.is_empty() {
+        depth += 1;
+        let mut next_frontier = Vec::new();
+        for index in frontier {
+            let (a, b) = (&a_moves[index / b_size], &b_moves[index % b_size]);
+            for m in 0..a.len() {
+                let next = a[m] as usize * b_size + b[m] as usize;
+                if distances[next] == UNSEEN {
+                    distances[next] = depth;
+                    next_frontier.push(next);
+                }
+            }
+        }
+        frontier = next_frontier;
+    }
+    distances
+}
+
+// Move and pruning tables of both phases. Building them takes about half a
+// second (seconds in a debug build), so they are meant to be built once and
+// shared.
+pub struct KociembaTables {
+    // Slice coordinate of every mask of four positions, and back
+    slice_of_mask: Vec<u16>,
+    slice_masks: Vec<usize>,
+    // Phase 1, for all 18 face turns in the order of Move::all
+    twist_moves: Vec<Vec<u16>>,
+    flip_moves: Vec<Vec<u16>>,
+    slice_moves: Vec<Vec<u16>>,
+    slice_twist: Vec<u8>,
+    slice_flip: Vec<u8>,
+    // Phase 2, for the moves of PHASE2_MOVES
+    corner_moves: Vec<Vec<u16>>,
+    edge_moves: Vec<Vec<u16>>,
+    slice_perm_moves: Vec<Vec<u16>>,
+    slice_corner: Vec<u8>,
+    slice_edge: Vec<u8>,
+}
+
+impl Default for KociembaTables {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KociembaTables {
+    pub fn new() -> Self {
+        let slice_masks: Vec<usize> = (0..1 << 12)
+            .filter(|mask: &usize| mask.count_ones() == 4)
+            .collect();
+        debug_assert_eq!(slice_masks.len(), SLICES);
+        let mut slice_of_mask = vec![0; 1 << 12];
+        for (slice, &mask) in slice_masks.iter().enumerate() {
+            slice_of_mask[mask] = slice as u16;
+        }
+        let slice = |cube: &CubieCube| slice_of_mask[slice_mask(cube)] as usize;
+        let solved_slice = slice(&CubieCube::new());
+
+        let all = Move::all();
+        let twist_moves = move_table(TWISTS, &all, with_twist, twist);
+        let flip_moves = move_table(FLIPS, &all, with_flip, flip);
+        let slice_moves = move_table(SLICES, &all, |s| with_slice_mask(slice_masks[s]), slice);
+        let corner_moves = move_table(CORNER_PERMS, &PHASE2_MOVES, with_corner_perm, corner_perm);
+        let edge_moves = move_table(EDGE_PERMS, &PHASE2_MOVES, with_edge_perm, edge_perm);
+        let slice_perm_moves = move_table(SLICE_PERMS, &PHASE2_MOVES, with_slice_perm, slice_perm);
+
+        KociembaTables {
+            slice_twist: pruning_table(&slice_moves, &twist_moves, (solved_slice, 0)),
+            slice_flip: pruning_table(&slice_moves, &flip_moves, (solved_slice, 0)),
+            slice_corner: pruning_table(&slice_perm_moves, &corner_moves, (0, 0)),
+            slice_edge: pruning_table(&slice_perm_moves, &edge_moves, (0, 0)),
+            slice_of_mask,
+            slice_masks,
+            twist_moves,
+            flip_moves,
+            slice_moves,
+            corner_moves,
+            edge_moves,
+            slice_perm_moves,
+        }
+    }
+
+    fn phase1_coordinates(&self, cube: &CubieCube) -> Phase1 {
+        Phase1 {
+            twist: twist(cube),
+            flip: flip(cube),
+            slice: self.slice_of_mask[slice_mask(cube)] as usize,
+        }
+    }
+
+    fn is_g1(&self, c: &Phase1) -> bool {
+        c.twist == 0 && c.flip == 0 && self.slice_masks[c.slice] == 0xf00
+    }
+
+    // Lower bound of the moves to G1
+    fn phase1_distance(&self, c: &Phase1) -> usize {
+        let twist = self.slice_twist[c.slice * TWISTS + c.twist];
+        let flip = self.slice_flip[c.slice * FLIPS + c.flip];
+        twist.max(flip) as usize
+    }
+
+    // Lower bound of the moves of G1 to solved
+    fn phase2_distance(&self, c: &Phase2) -> usize {
+        let corner = self.slice_corner[c.slice_perm * CORNER_PERMS + c.corner];
+        let edge = self.slice_edge[c.slice_perm * EDGE_PERMS + c.edge];
+        corner.max(edge) as usize
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Phase1 {
+    twist: usize,
+    flip: usize,
+    slice: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Phase2 {
+    corner: usize,
+    edge: usize,
+    slice_perm: usize,
+}
+
+struct TwoPhaseSearch<'a> {
+    tables: &'a KociembaTables,
+    cube: CubieCube,
+    // The moves of phase 1, in the order of the move tables
+    moves: Vec<Move>,
+    max_length: usize,
+    // Moves of both phases so far
+    phase1: Vec<Move>,
+    phase2: Vec<Move>,
+    nodes_expanded: usize,
+}
+
+impl TwoPhaseSearch<'_> {
+    // Tries the phase 1 solutions of exactly `remaining` more moves
+    fn phase1(&mut self, c: Phase1, remaining: usize) -> bool {
+        self.nodes_expanded += 1;
+        if remaining == 0 {
+            return self.tables.is_g1(&c) && self.start_phase2();
+        }
+        if self.tables.phase1_distance(&c) > remaining {
+            return false;
+        }
+        for i in 0..self.moves.len() {
+            let m = self.moves[i];
+            if !may_follow(self.phase1.last(), m) {
+                continue;
+            }
+            let next = Phase1 {
+                twist: self.tables.twist_moves[c.twist][i] as usize,
+                flip: self.tables.flip_moves[c.flip][i] as usize,
+                slice: self.tables.slice_moves[c.slice][i] as usize,
+            };
+            self.phase1.push(m);
+            if self.phase1(next, remaining - 1) {
+                return true;
+            }
+            self.phase1.pop();
+        }
+        false
+    }
+
+    fn start_phase2(&mut self) -> bool {
+        // Without its last move, which is one of G1, the same phase 1
+        // reached G1 before and its phase 2 was tried with a move to spare
+        if self.phase1.last().is_some_and(|&m| in_phase2(m)) {
+            return false;
+        }
+        let mut cube = self.cube;
+        for &m in &self.phase1 {
+            cube.apply_move(m);
+        }
+        let c = Phase2 {
+            corner: corner_perm(&cube),
+            edge: edge_perm(&cube),
+            slice_perm: slice_perm(&cube),
+        };
+        let budget = self.max_length - self.phase1.len();
+        (self.tables.phase2_distance(&c)..=budget).any(|depth| self.phase2(c, depth))
+    }
+
+    fn phase2(&mut self, c: Phase2, remaining: usize) -> bool {
+        self.nodes_expanded += 1;
+        if remaining == 0 {
+            return c.corner == 0 && c.edge == 0 && c.slice_perm == 0;
+        }
+        if self.tables.phase2_distance(&c) > remaining {
+            return false;
+        }
+        for (i, &m) in PHASE2_MOVES.iter().enumerate() {
+            let last = self.phase2.last().or(self.phase1.last());
+            if !may_follow(last, m) {
+                continue;
+            }
+            let next = Phase2 {
+                corner: self.tables.corner_moves[c.corner][i] as usize,
+                edge: self.tables.edge_moves[c.edge][i] as usize,
+                slice_perm: self.tables.slice_perm_moves[c.slice_perm][i] as usize,
+            };
+            self.phase2.push(m);
+            if self.phase2(next, remaining - 1) {
+                return true;
+            }
+            self.phase2.pop();
+        }
+        false
+    }
+}
+
+impl RubiksCube {
+    // A solution of at most `max_length` face turns, found with the
+    // two-phase algorithm. None if there is none that short or the
+    // stickers don't describe a real state.
+    pub fn solve_kociemba(
+        &self,
+        tables: &KociembaTables,
+        max_length: usize,
+    ) -> Option<SearchResult> {
+        let cube = self.validate().ok()?;
+        let start = tables.phase1_coordinates(&cube);
+        let mut search = TwoPhaseSearch {
+            tables,
+            cube,
+            moves: Move::all(),
+            max_length,
+            phase1: Vec::new(),
+            phase2: Vec::new(),
+            nodes_expanded: 0,
+        };
+        for depth in tables.phase1_distance(&start)..=max_length {
+            if search.phase1(start, depth) {
+                let solution = search
+                    .phase1
+                    .iter()
+                    .chain(&search.phase2)
+                    .map(Move::to_string)
+                    .collect();
+                return Some(SearchResult {
+                    solution,
+                    nodes_expanded: search.nodes_expanded,
+                });
+            }
+        }
+        None
+    }
+}
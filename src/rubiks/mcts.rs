@@ -0,0 +1,188 @@
+use super::astar::CostToGo;
+use super::cube::RubiksCube;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+// Estimators that also score the moves of all_moves (e.g. a policy network)
+pub trait Policy: CostToGo {
+    // Prior probability of each move in all_moves, summing to one
+    fn policy(&self, cube: &RubiksCube) -> Vec<f64>;
+}
+
+// Settings of the Monte Carlo tree search
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MctsConfig {
+    // Weight of the policy prior against the value estimate when selecting moves
+    pub exploration: f64,
+    pub max_simulations: usize,
+    // Seed for breaking ties between equally good moves
+    pub seed: u64,
+}
+
+impl Default for MctsConfig {
+    fn default() -> Self {
+        MctsConfig {
+            exploration: 2.0,
+            max_simulations: 10_000,
+            seed: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MctsResult {
+    pub solution: Vec<String>,
+    // Number of states in the search tree
+    pub tree_size: usize,
+    pub simulations: usize,
+}
+
+struct MctsNode {
+    cube: RubiksCube,
+    // Estimated cost-to-go: the model's estimate for a leaf, then
+    // 1 + the cheapest child once the node is expanded
+    cost: f64,
+    // Empty until the node is expanded, then one child per move
+    children: Vec<usize>,
+    prior: Vec<f64>,
+    visits: Vec<u32>,
+}
+
+impl MctsNode {
+    fn new(cube: RubiksCube, cost: f64) -> Self {
+        MctsNode {
+            cube,
+            cost,
+            children: Vec::new(),
+            prior: Vec::new(),
+            visits: Vec::new(),
+        }
+    }
+}
+
+// Move maximizing Q + U as in AlphaZero, with Q the negated cost-to-go of
+// the child. Ties are broken at random.
+fn select<R: Rng>(nodes: &[MctsNode], node: usize, exploration: f64, rng: &mut R) -> usize {
+    let node = &nodes[node];
+    let total_visits: u32 = node.visits.iter().sum();
+    let scores: Vec<f64> = (0..node.children.len())
+        .map(|a| {
+            -nodes[node.children[a]].cost
+                + exploration * node.prior[a] * (total_visits as f64 + 1.0).sqrt()
+                    / (1.0 + node.visits[a] as f64)
+        })
+        .collect();
+    let best_score = scores.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let best_moves: Vec<usize> = (0..scores.len())
+        .filter(|&a| best_score - scores[a] < 1e-9)
+        .collect();
+    best_moves[rng.gen_range(0..best_moves.len())]
+}
+
+impl RubiksCube {
+    // Monte Carlo tree search guided by policy priors and value estimates
+    // (DeepCube, McAleer et al. 2018). Every simulation walks down the tree,
+    // expands one leaf and backs the cheapest child cost up along the path, so
+    // optimistic estimates get corrected. Once a solved state shows up, the
+    // shortest path through the tree is returned.
+    pub fn solve_mcts<M: Policy>(&self, model: &M, config: &MctsConfig) -> Option<MctsResult> {
+        let moves = RubiksCube::new().all_moves();
+        let mut rng = StdRng::seed_from_u64(config.seed);
+        let mut nodes = vec![MctsNode::new(self.clone(), model.cost_to_go(self))];
+        let mut solved = self.is_solved().then_some(0);
+        let mut simulations = 0;
+
+        while solved.is_none() && simulations < config.max_simulations {
+            simulations += 1;
+
+            // Selection
+            let mut path = Vec::new();
+            let mut current = 0;
+            while !nodes[current].children.is_empty() {
+                let a = select(&nodes, current, config.exploration, &mut rng);
+                path.push((current, a));
+                current = nodes[current].children[a];
+            }
+
+            // Expansion
+            let children: Vec<RubiksCube> = moves
+                .iter()
+                .map(|(_, move_fn)| {
+                    let mut next_cube = nodes[current].cube.clone();
+                    move_fn(&mut next_cube);
+                    next_cube
+                })
+                .collect();
+            let estimates = model.cost_to_go_batch(&children);
+            let prior = model.policy(&nodes[current].cube);
+            let first_child = nodes.len();
+            solved = children
+                .iter()
+                .position(|cube| cube.is_solved())
+                .map(|a| first_child + a);
+            nodes.extend(
+                children
+                    .into_iter()
+                    .zip(&estimates)
+                    .map(|(cube, &h)| MctsNode::new(cube, h)),
+            );
+            let leaf = &mut nodes[current];
+            leaf.children = (first_child..first_child + moves.len()).collect();
+            leaf.prior = prior;
+            leaf.visits = vec![0; moves.len()];
+            leaf.cost = 1.0 + estimates.iter().cloned().fold(f64::INFINITY, f64::min);
+
+            // Backup
+            for &(node, a) in path.iter().rev() {
+                let cheapest_child = nodes[node]
+                    .children
+                    .iter()
+                    .map(|&child| nodes[child].cost)
+                    .fold(f64::INFINITY, f64::min);
+                let node = &mut nodes[node];
+                node.visits[a] += 1;
+                node.cost = 1.0 + cheapest_child;
+            }
+        }
+
+        solved.map(|solved| MctsResult {
+            solution: shortest_tree_path(&nodes, solved),
+            tree_size: nodes.len(),
+            simulations,
+        })
+    }
+}
+
+// BFS from the root over the states in the tree, which can be shorter
+// than the path along which the solved state was found
+fn shortest_tree_path(nodes: &[MctsNode], solved: usize) -> Vec<String> {
+    let moves = RubiksCube::new().all_moves();
+    let states: HashSet<&RubiksCube> = nodes.iter().map(|node| &node.cube).collect();
+    let mut parents: HashMap<RubiksCube, (RubiksCube, &'static str)> = HashMap::new();
+    let mut queue = VecDeque::from([nodes[0].cube.clone()]);
+    let mut visited = HashSet::from([nodes[0].cube.clone()]);
+
+    while let Some(cube) = queue.pop_front() {
+        if cube == nodes[solved].cube {
+            let mut path = Vec::new();
+            let mut current = cube;
+            while let Some((parent, move_name)) = parents.get(&current) {
+                path.push(move_name.to_string());
+                current = parent.clone();
+            }
+            path.reverse();
+            return path;
+        }
+        for (move_name, move_fn) in &moves {
+            let mut next_cube = cube.clone();
+            move_fn(&mut next_cube);
+            if states.contains(&next_cube) && visited.insert(next_cube.clone()) {
+                parents.insert(next_cube.clone(), (cube.clone(), move_name));
+                queue.push_back(next_cube);
+            }
+        }
+    }
+    unreachable!("the solved state is reachable through the tree")
+}
@@ -0,0 +1,189 @@
+//! Ways of counting moves.
+//!
+//! - QTM (quarter-turn metric): a quarter turn of a face is one move, a
+//!   half turn two.
+//! - HTM (half-turn or face-turn metric): any turn of one face is one move.
+//! - STM (slice-turn metric): turns of a middle slice count as one move too.
+//! - ATM (axial-turn metric): turning any layers about the same axis at the
+//!   same time is one move, e.g. R L' or U2 D.
+//!
+//! The centres stay in place in `RubiksCube`, and a slice turn leaves the
+//! same state as turning the two outer layers the other way (M is R L' up
+//! to a rotation of the whole cube). So slice turns are written as such pairs
+//! of face turns, and every metric's moves are sequences of face turns.
+use super::cube::RubiksCube;
+use super::moves::{Face, Move, Turn};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Metric {
+    #[default]
+    Qtm,
+    Htm,
+    Stm,
+    Atm,
+}
+
+// The opposite faces U D, F B and R L
+const AXES: [(Face, Face); 3] = [(Face::U, Face::D), (Face::F, Face::B), (Face::R, Face::L)];
+
+// Clockwise quarter turns of 1, 2 or 3
+fn turn(quarter_turns: usize) -> Turn {
+    match quarter_turns % 4 {
+        1 => Turn::Clockwise,
+        2 => Turn::Double,
+        3 => Turn::CounterClockwise,
+        _ => panic!("no turn of {} quarter turns", quarter_turns),
+    }
+}
+
+fn axis(face: Face) -> usize {
+    AXES.iter()
+        .position(|&(a, b)| face == a || face == b)
+        .expect("every face is on an axis")
+}
+
+// One move of a metric
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetricMove {
+    // The face turns in standard notation, e.g. "R" or "R L'"
+    pub name: String,
+    pub turns: Vec<Move>,
+}
+
+impl MetricMove {
+    fn new(turns: Vec<Move>) -> Self {
+        let names: Vec<String> = turns.iter().map(|m| m.to_string()).collect();
+        MetricMove {
+            name: names.join(" "),
+            turns,
+        }
+    }
+
+    pub fn apply(&self, cube: &mut RubiksCube) {
+        cube.apply_moves(&self.turns);
+    }
+
+    pub fn inverse(&self) -> MetricMove {
+        MetricMove::new(self.turns.iter().map(|m| m.inverse()).collect())
+    }
+
+    // The face turns one by one, for solutions
+    pub fn turn_names(&self) -> impl Iterator<Item = String> + '_ {
+        self.turns.iter().map(|m| m.to_string())
+    }
+}
+
+impl Metric {
+    pub const ALL: [Metric; 4] = [Metric::Qtm, Metric::Htm, Metric::Stm, Metric::Atm];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Metric::Qtm => "qtm",
+            Metric::Htm => "htm",
+            Metric::Stm => "stm",
+            Metric::Atm => "atm",
+        }
+    }
+
+    // The moves the solvers search with. The quarter turns are listed like
+    // in RubiksCube::all_moves.
+    pub fn moves(self) -> Vec<MetricMove> {
+        let single = |face: Face, quarter_turns: usize| {
+            MetricMove::new(vec![Move::new(face, turn(quarter_turns))])
+        };
+        let order = [Face::U, Face::D, Face::F, Face::B, Face::L, Face::R];
+        match self {
+            Metric::Qtm => order
+                .iter()
+                .flat_map(|&face| [single(face, 1), single(face, 3)])
+                .collect(),
+            Metric::Htm => order
+                .iter()
+                .flat_map(|&face| [1, 2, 3].map(|q| single(face, q)))
+                .collect(),
+            Metric::Stm => {
+                let mut moves = Metric::Htm.moves();
+                for (a, b) in AXES {
+                    for q in [1, 2, 3] {
+                        let slice = vec![Move::new(a, turn(q)), Move::new(b, turn(4 - q))];
+                        moves.push(MetricMove::new(slice));
+                    }
+                }
+                moves
+            }
+            Metric::Atm => {
+                let mut moves = Vec::new();
+                for (a, b) in AXES {
+                    for qa in 0..4 {
+                        for qb in 0..4 {
+                            let turns: Vec<Move> = [(a, qa), (b, qb)]
+                                .into_iter()
+                                .filter(|&(_, q)| q > 0)
+                                .map(|(face, q)| Move::new(face, turn(q)))
+                                .collect();
+                            if !turns.is_empty() {
+                                moves.push(MetricMove::new(turns));
+                            }
+                        }
+                    }
+                }
+                moves
+            }
+        }
+    }
+
+    // Length of a sequence of face turns in this metric. Turns about the same
+    // axis are combined first, so R R counts like R2 and R L R like R2 L.
+    pub fn length(self, moves: &[Move]) -> usize {
+        // Quarter turns of both faces of every run of turns about one axis
+        let mut groups: Vec<(usize, [usize; 2])> = Vec::new();
+        for m in moves {
+            let axis = axis(m.face);
+            let side = (AXES[axis].1 == m.face) as usize;
+            if groups.last().map(|&(a, _)| a) != Some(axis) {
+                groups.push((axis, [0, 0]));
+            }
+            let (_, turns) = groups.last_mut().expect("just pushed");
+            turns[side] = (turns[side] + m.turn.quarter_turns()) % 4;
+            // A run that cancels out lets its neighbours combine
+            if *turns == [0, 0] {
+                groups.pop();
+            }
+        }
+
+        groups
+            .iter()
+            .map(|&(_, [a, b])| {
+                let faces = (a > 0) as usize + (b > 0) as usize;
+                let quarter = |q: usize| if q == 2 { 2 } else { (q > 0) as usize };
+                match self {
+                    Metric::Qtm => quarter(a) + quarter(b),
+                    Metric::Htm => faces,
+                    Metric::Stm if faces == 2 && (a + b) % 4 == 0 => 1,
+                    Metric::Stm => faces,
+                    Metric::Atm => 1,
+                }
+            })
+            .sum()
+    }
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Metric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Metric::ALL
+            .into_iter()
+            .find(|metric| metric.name() == s.to_lowercase())
+            .ok_or_else(|| format!("unknown metric {}, expected qtm, htm, stm or atm", s))
+    }
+}
@@ -1,3 +1,15 @@
+pub mod astar;
+pub mod batch;
+pub mod cases;
 pub mod color;
 pub mod cube;
+pub mod cubie;
+pub mod facelet;
+pub mod kociemba;
+pub mod mcts;
+pub mod metric;
+pub mod moves;
+pub mod permutation;
+pub mod pruning;
 pub mod solver;
+pub mod table;
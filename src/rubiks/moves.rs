@@ -0,0 +1,237 @@
+use super::cube::{MoveFn, RubiksCube};
+use rand::Rng;
+use std::fmt;
+use std::str::FromStr;
+
+// The six faces, in the same order as RubiksCube::faces
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Face {
+    U, // Up
+    D, // Down
+    F, // Front
+    B, // Back
+    R, // Right
+    L, // Left
+}
+
+impl Face {
+    pub const ALL: [Face; 6] = [Face::U, Face::D, Face::F, Face::B, Face::R, Face::L];
+
+    // Index of the face in RubiksCube::faces
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn opposite(self) -> Face {
+        match self {
+            Face::U => Face::D,
+            Face::D => Face::U,
+            Face::F => Face::B,
+            Face::B => Face::F,
+            Face::R => Face::L,
+            Face::L => Face::R,
+        }
+    }
+
+    pub fn letter(self) -> char {
+        match self {
+            Face::U => 'U',
+            Face::D => 'D',
+            Face::F => 'F',
+            Face::B => 'B',
+            Face::R => 'R',
+            Face::L => 'L',
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Turn {
+    Clockwise,
+    Double,
+    CounterClockwise,
+}
+
+impl Turn {
+    pub const ALL: [Turn; 3] = [Turn::Clockwise, Turn::Double, Turn::CounterClockwise];
+
+    // Number of clockwise quarter turns
+    pub fn quarter_turns(self) -> usize {
+        match self {
+            Turn::Clockwise => 1,
+            Turn::Double => 2,
+            Turn::CounterClockwise => 3,
+        }
+    }
+}
+
+// A single face turn in the standard notation, e.g. R, U' or F2
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Move {
+    pub face: Face,
+    pub turn: Turn,
+}
+
+impl Move {
+    pub const fn new(face: Face, turn: Turn) -> Self {
+        Move { face, turn }
+    }
+
+    // All 18 face turns
+    pub fn all() -> Vec<Move> {
+        Face::ALL
+            .iter()
+            .flat_map(|&face| Turn::ALL.iter().map(move |&turn| Move::new(face, turn)))
+            .collect()
+    }
+
+    pub fn inverse(self) -> Move {
+        let turn = match self.turn {
+            Turn::Clockwise => Turn::CounterClockwise,
+            Turn::Double => Turn::Double,
+            Turn::CounterClockwise => Turn::Clockwise,
+        };
+        Move::new(self.face, turn)
+    }
+
+    pub fn apply(self, cube: &mut RubiksCube) {
+        let (clockwise, counterclockwise): (MoveFn, MoveFn) = match self.face {
+            Face::U => (RubiksCube::u_clockwise, RubiksCube::u_counterclockwise),
+            Face::D => (RubiksCube::d_clockwise, RubiksCube::d_counterclockwise),
+            Face::F => (RubiksCube::f_clockwise, RubiksCube::f_counterclockwise),
+            Face::B => (RubiksCube::b_clockwise, RubiksCube::b_counterclockwise),
+            Face::R => (RubiksCube::r_clockwise, RubiksCube::r_counterclockwise),
+            Face::L => (RubiksCube::l_clockwise, RubiksCube::l_counterclockwise),
+        };
+        match self.turn {
+            Turn::Clockwise => clockwise(cube),
+            Turn::Double => {
+                clockwise(cube);
+                clockwise(cube);
+            }
+            Turn::CounterClockwise => counterclockwise(cube),
+        }
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let suffix = match self.turn {
+            Turn::Clockwise => "",
+            Turn::Double => "2",
+            Turn::CounterClockwise => "'",
+        };
+        write!(f, "{}{}", self.face.letter(), suffix)
+    }
+}
+
+impl FromStr for Move {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let face = match chars.next() {
+            Some('U') => Face::U,
+            Some('D') => Face::D,
+            Some('F') => Face::F,
+            Some('B') => Face::B,
+            Some('R') => Face::R,
+            Some('L') => Face::L,
+            _ => return Err(format!("invalid move '{}'", s)),
+        };
+        // R2' is sometimes written for R2 and means the same
+        let turn = match chars.as_str() {
+            "" => Turn::Clockwise,
+            "'" => Turn::CounterClockwise,
+            "2" | "2'" => Turn::Double,
+            _ => return Err(format!("invalid move '{}'", s)),
+        };
+        Ok(Move::new(face, turn))
+    }
+}
+
+// Moves are stored in the standard notation, e.g. "R'", so that algorithms
+// read like a list of the moves
+#[cfg(feature = "serde")]
+impl serde::Serialize for Move {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Move {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+// Parse a whitespace separated sequence of moves, e.g. "R U R' U'"
+pub fn parse_algorithm(algorithm: &str) -> Result<Vec<Move>, String> {
+    algorithm.split_whitespace().map(str::parse).collect()
+}
+
+// The moves that undo `algorithm`
+pub fn invert_algorithm(algorithm: &[Move]) -> Vec<Move> {
+    algorithm.iter().rev().map(|m| m.inverse()).collect()
+}
+
+// Random-move scramble in the style used at competitions: no face is turned
+// twice in a row, and opposite faces aren't turned again after one another
+// (as in R L R), since both would just shorten the scramble
+pub fn generate_scramble<R: Rng>(rng: &mut R, length: usize) -> Vec<Move> {
+    let moves: Vec<Move> = Face::ALL
+        .iter()
+        .flat_map(|&face| Turn::ALL.iter().map(move |&turn| Move::new(face, turn)))
+        .collect();
+    let mut scramble: Vec<Move> = Vec::with_capacity(length);
+    while scramble.len() < length {
+        let m = moves[rng.gen_range(0..moves.len())];
+        let redundant = match scramble.as_slice() {
+            [.., last] if last.face == m.face => true,
+            [.., before, last] => before.face == m.face && last.face == m.face.opposite(),
+            _ => false,
+        };
+        if !redundant {
+            scramble.push(m);
+        }
+    }
+    scramble
+}
+
+pub fn algorithm_to_string(algorithm: &[Move]) -> String {
+    algorithm
+        .iter()
+        .map(|m| m.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl RubiksCube {
+    // The state the moves make from the solved cube
+    pub fn from_moves(moves: &[Move]) -> Self {
+        let mut cube = RubiksCube::new();
+        cube.apply_moves(moves);
+        cube
+    }
+
+    // The state a scramble in standard notation makes from the solved cube
+    pub fn from_scramble(scramble: &str) -> Self {
+        let mut cube = RubiksCube::new();
+        cube.apply_scramble(scramble);
+        cube
+    }
+
+    pub fn apply_move(&mut self, m: Move) {
+        m.apply(self);
+    }
+
+    pub fn apply_moves(&mut self, moves: &[Move]) {
+        for &m in moves {
+            m.apply(self);
+        }
+    }
+}
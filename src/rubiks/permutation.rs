@@ -0,0 +1,160 @@
+//! The cube as a permutation group.
+//!
+//! A state (or the algorithm that makes it from the solved cube) moves the
+//! corners and the edges around in disjoint cycles. A piece going once
+//! around its cycle comes back twisted by the sum of the orientations it is
+//! given along the way, so a cycle of length n with a twist is only undone
+//! after 3n (corners) or 2n (edges) applications. The order of an algorithm,
+//! the number of times it has to be repeated to get back to solved, is the
+//! least common multiple of these.
+//!
+//! Cycles are written in the usual notation, following the pieces: in
+//! `(URF UBR DRB DFR)` the corner in URF moves to UBR, the one in UBR to DRB
+//! and so on. A `+` after a cycle means its pieces come back twisted
+//! clockwise (or flipped for edges), a `-` counterclockwise. A piece twisted
+//! in place is a cycle of one, e.g. `(UF)+`.
+use super::cube::RubiksCube;
+use super::cubie::{CubieCube, StateError, CORNER_NAMES, EDGE_NAMES};
+use super::moves::Move;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    // Positions in the order the pieces move through them, starting at the
+    // lowest
+    pub positions: Vec<usize>,
+    // Twist (mod 3) or flip (mod 2) a piece picks up going once around
+    pub twist: u8,
+}
+
+// Every cycle of a state except the pieces that stay solved
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CycleStructure {
+    pub corners: Vec<Cycle>,
+    pub edges: Vec<Cycle>,
+}
+
+fn cycles(perm: &[u8], orient: &[u8], modulus: u8) -> Vec<Cycle> {
+    // perm[i] is the piece in position i, so the piece from position
+    // perm[i] goes to i
+    let mut next = vec![0; perm.len()];
+    for (i, &from) in perm.iter().enumerate() {
+        next[from as usize] = i;
+    }
+    let mut seen = vec![false; perm.len()];
+    let mut cycles = Vec::new();
+    for start in 0..perm.len() {
+        if seen[start] {
+            continue;
+        }
+        let mut positions = Vec::new();
+        let mut twist = 0;
+        let mut position = start;
+        while !seen[position] {
+            seen[position] = true;
+            positions.push(position);
+            position = next[position];
+            twist = (twist + orient[position]) % modulus;
+        }
+        if positions.len() > 1 || twist != 0 {
+            cycles.push(Cycle { positions, twist });
+        }
+    }
+    cycles
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+impl CycleStructure {
+    // Number of applications after which every piece is back and untwisted
+    pub fn order(&self) -> usize {
+        let corners = self
+            .corners
+            .iter()
+            .map(|c| c.positions.len() * [1, 3, 3][c.twist as usize]);
+        let edges = self
+            .edges
+            .iter()
+            .map(|c| c.positions.len() * [1, 2][c.twist as usize]);
+        corners
+            .chain(edges)
+            .fold(1, |order, n| order / gcd(order, n) * n)
+    }
+
+    // Whether the corners (and so also the edges) are permuted by an odd
+    // number of swaps, as after an odd number of quarter turns
+    pub fn is_odd(&self) -> bool {
+        let swaps: usize = self.corners.iter().map(|c| c.positions.len() - 1).sum();
+        swaps % 2 == 1
+    }
+
+    // Positions of the corners that are moved or twisted, in order
+    pub fn affected_corners(&self) -> Vec<usize> {
+        affected(&self.corners)
+    }
+
+    // Positions of the edges that are moved or flipped, in order
+    pub fn affected_edges(&self) -> Vec<usize> {
+        affected(&self.edges)
+    }
+}
+
+fn affected(cycles: &[Cycle]) -> Vec<usize> {
+    let mut positions: Vec<usize> = cycles.iter().flat_map(|c| c.positions.clone()).collect();
+    positions.sort_unstable();
+    positions
+}
+
+// Cycles in notation, with the mark of every twist
+fn notation(cycles: &[Cycle], names: &[&str], twists: &[&str]) -> Vec<String> {
+    cycles
+        .iter()
+        .map(|cycle| {
+            let pieces: Vec<&str> = cycle.positions.iter().map(|&p| names[p]).collect();
+            format!("({}){}", pieces.join(" "), twists[cycle.twist as usize])
+        })
+        .collect()
+}
+
+impl fmt::Display for CycleStructure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut cycles = notation(&self.corners, &CORNER_NAMES, &["", "+", "-"]);
+        cycles.extend(notation(&self.edges, &EDGE_NAMES, &["", "+"]));
+        if cycles.is_empty() {
+            write!(f, "()")
+        } else {
+            write!(f, "{}", cycles.join(" "))
+        }
+    }
+}
+
+impl CubieCube {
+    // The state an algorithm makes from the solved cube
+    pub fn from_moves(moves: &[Move]) -> Self {
+        let mut cube = CubieCube::new();
+        for &m in moves {
+            cube.apply_move(m);
+        }
+        cube
+    }
+
+    pub fn cycles(&self) -> CycleStructure {
+        CycleStructure {
+            corners: cycles(&self.corner_perm, &self.corner_orient, 3),
+            edges: cycles(&self.edge_perm, &self.edge_orient, 2),
+        }
+    }
+}
+
+impl RubiksCube {
+    // Cycles of the pieces, if the stickers describe a real state
+    pub fn cycles(&self) -> Result<CycleStructure, Vec<StateError>> {
+        self.validate().map(|cubie| cubie.cycles())
+    }
+}
@@ -0,0 +1,159 @@
+//! Canonical move sequences.
+//!
+//! Many move sequences lead to the same state: R R' does nothing, R R2 is
+//! R', and turns of opposite faces commute, so U D and D U are the same. A
+//! search that tries every move from every node explores each of these.
+//! Turns about one axis only ever add up to a quarter turn amount for each
+//! of its two faces, so for every such amount exactly one shortest way of
+//! making it out of the moves of the metric is canonical (the first in the
+//! order of `Metric::moves`), and a sequence is canonical if each run of
+//! turns about one axis is. Every state can be reached by a canonical
+//! sequence as short as its optimal solution.
+//!
+//! `SuccessorGenerator` walks canonical sequences one move at a time. Its
+//! state is the run of the last axis, and it only offers the moves that keep
+//! the sequence canonical: no turn of a face just turned (except R R in QTM)
+//! and the faces of an axis in a fixed order. In HTM this cuts the branching
+//! factor from 18 to about 13.35, in QTM from 12 to about 9.4.
+//!
+//! BFS, IDA*, weighted A* and the distance table search with it. MCTS keeps
+//! all twelve quarter turns as children, one for every output of the policy.
+use super::metric::{Metric, MetricMove};
+use std::collections::HashMap;
+
+// State at the start of a search, before any move
+pub const START: usize = 0;
+
+// A set of states, with bit s set for state s. Searches that meet a node
+// again on another path of the same length keep the states of both paths.
+pub type StateSet = u64;
+
+// Turns of one axis: the axis (0 for U D, 1 for F B, 2 for R L) and the
+// quarter turns of both faces
+type Run = (usize, [usize; 2]);
+
+fn run_of(m: &MetricMove) -> Run {
+    let axis = m.turns[0].face.index() / 2;
+    let mut turns = [0, 0];
+    for turn in &m.turns {
+        debug_assert_eq!(turn.face.index() / 2, axis);
+        turns[turn.face.index() % 2] += turn.turn.quarter_turns();
+    }
+    (axis, turns)
+}
+
+fn state_of((axis, [a, b]): Run) -> usize {
+    1 + 16 * axis + 4 * (a % 4) + b % 4
+}
+
+pub struct SuccessorGenerator {
+    metric: Metric,
+    moves: Vec<MetricMove>,
+    // The next state for each state and move, None if the move is pruned
+    transitions: Vec<Vec<Option<usize>>>,
+}
+
+impl SuccessorGenerator {
+    pub fn new(metric: Metric) -> Self {
+        let moves = metric.moves();
+        let runs: Vec<Run> = moves.iter().map(run_of).collect();
+
+        // The canonical way of making every run, found by trying the
+        // sequences of each axis in order, shortest first
+        let mut canonical: HashMap<Run, Vec<usize>> = HashMap::new();
+        for axis in 0..3 {
+            let axis_moves: Vec<usize> = (0..moves.len()).filter(|&i| runs[i].0 == axis).collect();
+            let mut sequences: Vec<Vec<usize>> = vec![vec![]];
+            while canonical.len() < 15 * (axis + 1) {
+                sequences = sequences
+                    .iter()
+                    .flat_map(|sequence| {
+                        axis_moves.iter().map(move |&i| {
+                            let mut longer = sequence.clone();
+                            longer.push(i);
+                            longer
+                        })
+                    })
+                    .collect();
+                for sequence in &sequences {
+                    let mut turns = [0, 0];
+                    for &i in sequence {
+                        turns[0] = (turns[0] + runs[i].1[0]) % 4;
+                        turns[1] = (turns[1] + runs[i].1[1]) % 4;
+                    }
+                    if turns != [0, 0] {
+                        canonical
+                            .entry((axis, turns))
+                            .or_insert_with(|| sequence.clone());
+                    }
+                }
+            }
+        }
+
+        let mut transitions = vec![vec![None; moves.len()]; state_of((2, [3, 3])) + 1];
+        debug_assert!(transitions.len() <= StateSet::BITS as usize);
+        transitions[START] = (0..moves.len()).map(|i| Some(state_of(runs[i]))).collect();
+        for (&(axis, [a, b]), sequence) in &canonical {
+            let state = state_of((axis, [a, b]));
+            for (i, &(move_axis, [ma, mb])) in runs.iter().enumerate() {
+                if move_axis != axis {
+                    transitions[state][i] = Some(state_of(runs[i]));
+                    continue;
+                }
+                // Only if the run goes on the canonical way
+                let run = (axis, [(a + ma) % 4, (b + mb) % 4]);
+                if canonical.get(&run) == Some(&[&sequence[..], &[i]].concat()) {
+                    transitions[state][i] = Some(state_of(run));
+                }
+            }
+        }
+
+        SuccessorGenerator {
+            metric,
+            moves,
+            transitions,
+        }
+    }
+
+    pub fn metric(&self) -> Metric {
+        self.metric
+    }
+
+    pub fn moves(&self) -> &[MetricMove] {
+        &self.moves
+    }
+
+    // Number of states, all below this
+    pub fn states(&self) -> usize {
+        self.transitions.len()
+    }
+
+    // The moves that keep the sequence canonical, as (index in moves, move,
+    // state after it)
+    pub fn successors(&self, state: usize) -> impl Iterator<Item = (usize, &MetricMove, usize)> {
+        self.transitions[state]
+            .iter()
+            .enumerate()
+            .filter_map(|(i, next)| next.map(|next| (i, &self.moves[i], next)))
+    }
+
+    // The moves that keep some sequence canonical that ends in one of the
+    // states, each with the set of states after it
+    pub fn successors_of_set(
+        &self,
+        states: StateSet,
+    ) -> impl Iterator<Item = (usize, &MetricMove, StateSet)> {
+        (0..self.moves.len()).filter_map(move |i| {
+            let mut next = 0;
+            let mut rest = states;
+            while rest != 0 {
+                let state = rest.trailing_zeros() as usize;
+                rest &= rest - 1;
+                if let Some(state) = self.transitions[state][i] {
+                    next |= 1 << state;
+                }
+            }
+            (next != 0).then(|| (i, &self.moves[i], next))
+        })
+    }
+}
@@ -1,38 +1,175 @@
 use super::cube::RubiksCube;
-use std::collections::{HashSet, VecDeque};
+use super::metric::Metric;
+use super::pruning::{StateSet, SuccessorGenerator, START};
+use super::table::DistanceTable;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+// A solution together with the effort it took to find it. The solution is
+// written as face turns whatever the metric; Metric::length counts them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SearchResult {
+    pub solution: Vec<String>,
+    pub nodes_expanded: usize,
+}
 
 //TODO: Implement the solver
 impl RubiksCube {
     pub fn solve(&self) -> Option<Vec<String>> {
-        let mut queue = VecDeque::new();
-        let mut visited = HashSet::new();
+        self.solve_bfs().map(|result| result.solution)
+    }
+
+    // Shortest solution in quarter turns
+    pub fn solve_bfs(&self) -> Option<SearchResult> {
+        self.solve_bfs_with(Metric::Qtm)
+    }
 
-        // Start BFS with the initial state
-        queue.push_back((self.clone(), vec![])); // (current state, moves to reach it)
-        visited.insert(self.to_string());
+    pub fn solve_bfs_with(&self, metric: Metric) -> Option<SearchResult> {
+        let generator = SuccessorGenerator::new(metric);
+        // Depth of every state seen and its place in the list of its depth
+        let mut visited = HashMap::from([(self.to_string(), (0, 0))]);
+        let mut nodes_expanded = 0;
 
-        while let Some((mut current_cube, path)) = queue.pop_front() {
-            // Check if the cube is solved
-            if current_cube.is_solved() {
-                return Some(path); // Return the sequence of moves
-            }
+        // Every state of the current depth once, with the moves to reach it
+        // and the pruning states of all the ways it was reached
+        let mut level: Vec<(RubiksCube, Vec<String>, StateSet)> =
+            vec![(self.clone(), vec![], 1 << START)];
+
+        let mut depth = 0;
+        while !level.is_empty() {
+            depth += 1;
+            let mut next_level: Vec<(RubiksCube, Vec<String>, StateSet)> = Vec::new();
+            for (current_cube, path, states) in level {
+                // Check if the cube is solved
+                if current_cube.is_solved() {
+                    // Return the sequence of moves
+                    return Some(SearchResult {
+                        solution: path,
+                        nodes_expanded,
+                    });
+                }
+                nodes_expanded += 1;
 
-            // Explore all possible moves
-            for (move_name, move_fn) in current_cube.all_moves() {
-                let mut next_cube = current_cube.clone();
-                move_fn(&mut next_cube);
-
-                // Serialize the state for comparison
-                let next_state = next_cube.to_string();
-                if !visited.contains(&next_state) {
-                    visited.insert(next_state);
-                    let mut new_path = path.clone();
-                    new_path.push(move_name.to_string());
-                    queue.push_back((next_cube, new_path));
+                // Explore the moves that keep the sequence canonical
+                for (_, m, next_states) in generator.successors_of_set(states) {
+                    let mut next_cube = current_cube.clone();
+                    m.apply(&mut next_cube);
+
+                    // Serialize the state for comparison
+                    match visited.entry(next_cube.to_string()) {
+                        Entry::Vacant(entry) => {
+                            entry.insert((depth, next_level.len()));
+                            let mut new_path = path.clone();
+                            new_path.extend(m.turn_names());
+                            next_level.push((next_cube, new_path, next_states));
+                        }
+                        // Reached again at the same depth in another way
+                        Entry::Occupied(entry) if entry.get().0 == depth => {
+                            next_level[entry.get().1].2 |= next_states;
+                        }
+                        Entry::Occupied(_) => {}
+                    }
                 }
             }
+            level = next_level;
         }
 
         None // No solution found (shouldn't happen for a valid Rubik's Cube)
     }
 }
+
+// Outcome of one bounded depth-first pass of IDA*
+enum Search {
+    Found,
+    NextBound(usize),
+}
+
+impl RubiksCube {
+    // Optimal solver: iterative deepening A* with the distance table as
+    // heuristic. Searches in the metric of the table and gives up once no
+    // solution of at most `max_depth` moves of it exists.
+    pub fn solve_ida(&self, table: &DistanceTable, max_depth: usize) -> Option<SearchResult> {
+        let generator = SuccessorGenerator::new(table.metric());
+        let context = IdaContext { table, generator };
+        let mut path = Vec::new();
+        let mut nodes_expanded = 0;
+        let mut bound = table.heuristic(self);
+
+        while bound <= max_depth {
+            match ida_search(
+                self,
+                0,
+                bound,
+                START,
+                &context,
+                &mut path,
+                &mut nodes_expanded,
+            ) {
+                Search::Found => {
+                    return Some(SearchResult {
+                        solution: path,
+                        nodes_expanded,
+                    })
+                }
+                Search::NextBound(next_bound) => bound = next_bound,
+            }
+        }
+
+        None
+    }
+}
+
+// What stays the same during an IDA* search
+struct IdaContext<'a> {
+    table: &'a DistanceTable,
+    generator: SuccessorGenerator,
+}
+
+fn ida_search(
+    cube: &RubiksCube,
+    depth: usize,
+    bound: usize,
+    state: usize,
+    context: &IdaContext,
+    path: &mut Vec<String>,
+    nodes_expanded: &mut usize,
+) -> Search {
+    let table = context.table;
+    // Inside the table the remaining distance is exact, so finish greedily
+    if let Some(distance) = table.get(cube) {
+        if depth + distance <= bound {
+            path.extend(table.descend(cube).expect("state is in the table"));
+            return Search::Found;
+        }
+        return Search::NextBound(depth + distance);
+    }
+
+    let estimate = depth + table.depth() + 1;
+    if estimate > bound {
+        return Search::NextBound(estimate);
+    }
+
+    *nodes_expanded += 1;
+    let mut next_bound = usize::MAX;
+    for (_, m, next_state) in context.generator.successors(state) {
+        let mut next_cube = cube.clone();
+        m.apply(&mut next_cube);
+        let length = path.len();
+        path.extend(m.turn_names());
+        match ida_search(
+            &next_cube,
+            depth + 1,
+            bound,
+            next_state,
+            context,
+            path,
+            nodes_expanded,
+        ) {
+            Search::Found => return Search::Found,
+            Search::NextBound(b) => next_bound = next_bound.min(b),
+        }
+        path.truncate(length);
+    }
+    Search::NextBound(next_bound)
+}
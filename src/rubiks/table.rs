@@ -0,0 +1,108 @@
+use super::cube::RubiksCube;
+use super::metric::Metric;
+use super::pruning::{StateSet, SuccessorGenerator, START};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+// Exact distances (in moves of `metric`) of every state that lies within
+// `depth` moves of the solved cube. Built by a BFS outwards from solved.
+pub struct DistanceTable {
+    depth: usize,
+    metric: Metric,
+    distances: HashMap<RubiksCube, u8>,
+}
+
+impl DistanceTable {
+    // Table of quarter turn distances
+    pub fn new(depth: usize) -> Self {
+        DistanceTable::with_metric(depth, Metric::Qtm)
+    }
+
+    pub fn with_metric(depth: usize, metric: Metric) -> Self {
+        let generator = SuccessorGenerator::new(metric);
+        let mut distances = HashMap::new();
+        // States of the last depth, with the pruning states they were reached in
+        let mut frontier = HashMap::from([(RubiksCube::new(), 1 << START)]);
+        distances.insert(RubiksCube::new(), 0);
+
+        for d in 1..=depth {
+            let mut next_frontier: HashMap<RubiksCube, StateSet> = HashMap::new();
+            for (cube, states) in &frontier {
+                for (_, m, next_states) in generator.successors_of_set(*states) {
+                    let mut next_cube = cube.clone();
+                    m.apply(&mut next_cube);
+                    match distances.entry(next_cube) {
+                        Entry::Vacant(entry) => {
+                            next_frontier.insert(entry.key().clone(), next_states);
+                            entry.insert(d as u8);
+                        }
+                        Entry::Occupied(entry) if *entry.get() as usize == d => {
+                            *next_frontier.get_mut(entry.key()).expect("in the frontier") |=
+                                next_states;
+                        }
+                        Entry::Occupied(_) => {}
+                    }
+                }
+            }
+            frontier = next_frontier;
+        }
+
+        DistanceTable {
+            depth,
+            metric,
+            distances,
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn metric(&self) -> Metric {
+        self.metric
+    }
+
+    pub fn len(&self) -> usize {
+        self.distances.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.distances.is_empty()
+    }
+
+    // Exact distance if the state is in the table
+    pub fn get(&self, cube: &RubiksCube) -> Option<usize> {
+        self.distances.get(cube).map(|&d| d as usize)
+    }
+
+    // Admissible estimate: exact inside the table, and every state outside
+    // of it needs at least depth + 1 moves
+    pub fn heuristic(&self, cube: &RubiksCube) -> usize {
+        self.get(cube).unwrap_or(self.depth + 1)
+    }
+
+    // Optimal solution for a state inside the table, found by always stepping
+    // to a neighbour that is one move closer to solved. Written as face turns,
+    // so a slice turn of STM takes up two entries.
+    pub fn descend(&self, cube: &RubiksCube) -> Option<Vec<String>> {
+        let moves = self.metric.moves();
+        let mut current = cube.clone();
+        let mut distance = self.get(&current)?;
+        let mut path = Vec::with_capacity(distance);
+
+        while distance > 0 {
+            let (m, next_cube) = moves
+                .iter()
+                .map(|m| {
+                    let mut next_cube = current.clone();
+                    m.apply(&mut next_cube);
+                    (m, next_cube)
+                })
+                .find(|(_, next_cube)| self.get(next_cube) == Some(distance - 1))?;
+            path.extend(m.turn_names());
+            current = next_cube;
+            distance -= 1;
+        }
+        Some(path)
+    }
+}
@@ -0,0 +1,88 @@
+//! Animated playback of face turns.
+//!
+//! Moves are applied to the model immediately; the animation only lags the
+//! picture behind. It keeps the state shown before the current turn and a
+//! queue of the turns still to be played, so input is never blocked.
+use crate::rubiks::cube::RubiksCube;
+use crate::rubiks::moves::Move;
+use std::collections::VecDeque;
+use std::ops::RangeInclusive;
+
+// Quarter turns per second the speed can be set to
+pub const SPEEDS: RangeInclusive<f32> = 1.0..=20.0;
+
+pub struct Animation {
+    // State before the turn that is currently played
+    shown: RubiksCube,
+    queue: VecDeque<Move>,
+    // Fraction of the current turn that has been played
+    progress: f32,
+    // Quarter turns per second
+    pub speed: f32,
+    pub enabled: bool,
+}
+
+impl Animation {
+    pub fn new(cube: RubiksCube) -> Self {
+        Animation {
+            shown: cube,
+            queue: VecDeque::new(),
+            progress: 0.0,
+            speed: 4.0,
+            enabled: true,
+        }
+    }
+
+    pub fn push(&mut self, m: Move) {
+        if self.enabled {
+            self.queue.push_back(m);
+        } else {
+            self.shown.apply_move(m);
+        }
+    }
+
+    // Show `cube` right away and drop all pending turns
+    pub fn jump_to(&mut self, cube: &RubiksCube) {
+        self.shown = cube.clone();
+        self.queue.clear();
+        self.progress = 0.0;
+    }
+
+    // Advances by `dt` seconds and returns whether turns are still playing
+    pub fn update(&mut self, dt: f32) -> bool {
+        // Catch up when moves come in faster than they can be played
+        let speed = self.speed * self.queue.len().max(1) as f32;
+        let mut remaining = dt * speed;
+        while let Some(&m) = self.queue.front() {
+            let quarter_turns = if m.turn.quarter_turns() == 2 {
+                2.0
+            } else {
+                1.0
+            };
+            let step = remaining / quarter_turns;
+            if self.progress + step < 1.0 {
+                self.progress += step;
+                break;
+            }
+            remaining -= (1.0 - self.progress) * quarter_turns;
+            self.shown.apply_move(m);
+            self.queue.pop_front();
+            self.progress = 0.0;
+        }
+        !self.queue.is_empty()
+    }
+
+    // State before the current turn
+    pub fn shown(&self) -> &RubiksCube {
+        &self.shown
+    }
+
+    // The turn being played and how far it has got, between 0 and 1
+    pub fn current(&self) -> Option<(Move, f32)> {
+        self.queue.front().map(|&m| (m, self.progress))
+    }
+
+    pub fn pending(&self) -> usize {
+        self.queue.len()
+    }
+}
@@ -1,60 +1,1387 @@
+use crate::render::net_sticker_offset;
+use crate::rubiks::cases::CaseSet;
+use crate::rubiks::color::Color;
 use crate::rubiks::cube::RubiksCube;
+use crate::rubiks::moves::{
+    algorithm_to_string, generate_scramble, invert_algorithm, parse_algorithm, Face, Move, Turn,
+};
+use crate::ui::animation::{Animation, SPEEDS};
+use crate::ui::editor::StateEditor;
+use crate::ui::player::{
+    SolutionPlayer, SolveJob, SolverChoice, SolverSettings, MAX_DEPTHS, TABLE_DEPTHS,
+};
+use crate::ui::scheme::{load_schemes, ColorScheme, SCHEME_FILE};
+use crate::ui::session::{Bookmark, Session};
+use crate::ui::timer::{format_result, Penalty, Solve, Timer, TimerSession, TimerState};
+use crate::ui::trainer::{Trainer, TrainerMode};
+use crate::ui::view3d::{drag_turn, draw_cube_3d, sticker_under, Camera};
 use eframe::egui;
-use eframe::egui::{Painter, Pos2, Rect, Vec2};
+use eframe::egui::{
+    Align2, Color32, FontId, Key, Painter, PointerButton, Pos2, Rect, RichText, Sense, Shape,
+    Stroke, Vec2,
+};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::f32::consts::{FRAC_PI_2, PI};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum View {
+    Net,
+    Perspective,
+}
+
+// What a mouse drag in the 3D view does
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Drag {
+    Orbit,
+    // Turn the face on which the drag started
+    Turn { face: Face, start: Pos2 },
+}
+
+// Solver running in the background, with the state it was started on
+struct PendingSolve {
+    job: SolveJob,
+    cube: RubiksCube,
+}
+
+// Speedcubing timer with its session and the scramble of the next solve
+struct TimerMode {
+    timer: Timer,
+    session: TimerSession,
+    scramble: Vec<Move>,
+    rng: StdRng,
+    export_path: String,
+    // Where the session was exported to, or why that failed
+    export_status: Option<Result<String, String>>,
+}
+
+const SCRAMBLE_LENGTH: usize = 20;
+
+// OLL/PLL drills
+struct Training {
+    trainer: Trainer,
+    rng: StdRng,
+    show_algorithm: bool,
+}
+
+// Key of the session in eframe's storage
+const SESSION_KEY: &str = "session";
 
 pub struct MyApp {
     pub cube: RubiksCube,
+    // Moves applied since the last reset
+    pub history: Vec<Move>,
+    // Undone moves, the next one to redo last
+    pub redo: Vec<Move>,
+    pub bookmarks: Vec<Bookmark>,
+    pub view: View,
+    pub camera: Camera,
+    pub animation: Animation,
+    pub solver_settings: SolverSettings,
+    // Scheme the stickers are drawn in
+    pub scheme: ColorScheme,
+    // Built-in schemes followed by the ones loaded from a file
+    schemes: Vec<ColorScheme>,
+    scheme_path: String,
+    scheme_error: Option<String>,
+    drag: Option<Drag>,
+    scramble_input: String,
+    scramble_error: Option<String>,
+    bookmark_name: String,
+    solving: Option<PendingSolve>,
+    player: Option<SolutionPlayer>,
+    // Sticker entry, replacing the moves and the 3D view while open
+    editor: Option<StateEditor>,
+    timer: Option<TimerMode>,
+    training: Option<Training>,
+    // Result of the last solve, or why it failed
+    solve_status: Option<Result<String, String>>,
 }
 
 impl MyApp {
     pub fn new(cube: RubiksCube) -> Self {
-        Self { cube }
+        Self {
+            animation: Animation::new(cube.clone()),
+            cube,
+            history: Vec::new(),
+            redo: Vec::new(),
+            bookmarks: Vec::new(),
+            view: View::Net,
+            camera: Camera::default(),
+            solver_settings: SolverSettings::default(),
+            scheme: ColorScheme::default(),
+            schemes: ColorScheme::builtin(),
+            scheme_path: SCHEME_FILE.to_string(),
+            scheme_error: None,
+            drag: None,
+            scramble_input: String::new(),
+            scramble_error: None,
+            bookmark_name: String::new(),
+            solving: None,
+            player: None,
+            editor: None,
+            timer: None,
+            training: None,
+            solve_status: None,
+        }
+    }
+
+    pub fn from_session(session: Session) -> Self {
+        let mut app = MyApp::new(session.cube.clone());
+        app.apply_session(session);
+        app
+    }
+
+    fn apply_session(&mut self, session: Session) {
+        self.animation.jump_to(&session.cube);
+        self.cube = session.cube;
+        self.history = session.history;
+        self.redo = session.redo;
+        self.bookmarks = session.bookmarks;
+        self.view = session.view;
+        self.camera = session.camera;
+        self.animation.enabled = session.animate;
+        self.animation.speed = session.speed;
+        self.solver_settings = session.solver;
+        self.select_scheme(&session.scheme);
+        self.scheme.show_letters = session.letters;
+    }
+
+    // Switches to the scheme called `name`, keeping whether letters are shown
+    fn select_scheme(&mut self, name: &str) {
+        if let Some(scheme) = self.schemes.iter().find(|scheme| scheme.name == name) {
+            self.scheme = ColorScheme {
+                show_letters: self.scheme.show_letters,
+                ..scheme.clone()
+            };
+        }
+    }
+
+    // Replaces the user schemes by the ones in `scheme_path`
+    fn load_schemes(&mut self) {
+        match load_schemes(&self.scheme_path) {
+            Ok(schemes) => {
+                self.schemes = ColorScheme::builtin();
+                self.schemes.extend(schemes);
+                self.scheme_error = None;
+                let name = self.scheme.name.clone();
+                self.select_scheme(&name);
+            }
+            Err(e) => self.scheme_error = Some(e),
+        }
+    }
+
+    fn scheme_controls(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Colours");
+        let mut selected = None;
+        egui::ComboBox::from_id_salt("color_scheme")
+            .selected_text(self.scheme.name.as_str())
+            .show_ui(ui, |ui| {
+                for scheme in &self.schemes {
+                    if ui
+                        .selectable_label(scheme.name == self.scheme.name, scheme.name.as_str())
+                        .clicked()
+                    {
+                        selected = Some(scheme.clone());
+                    }
+                }
+            });
+        if let Some(scheme) = selected {
+            // The colour-blind scheme turns the letters on
+            self.scheme = ColorScheme {
+                show_letters: self.scheme.show_letters || scheme.show_letters,
+                ..scheme
+            };
+        }
+        ui.checkbox(&mut self.scheme.show_letters, "Letters on stickers");
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.scheme_path);
+            if ui.button("Load").clicked() {
+                self.load_schemes();
+            }
+        });
+        if let Some(error) = &self.scheme_error {
+            ui.colored_label(Color32::RED, error);
+        }
+    }
+
+    pub fn session(&self) -> Session {
+        Session {
+            cube: self.cube.clone(),
+            history: self.history.clone(),
+            redo: self.redo.clone(),
+            bookmarks: self.bookmarks.clone(),
+            view: self.view,
+            camera: self.camera,
+            animate: self.animation.enabled,
+            speed: self.animation.speed,
+            solver: self.solver_settings,
+            scheme: self.scheme.name.clone(),
+            letters: self.scheme.show_letters,
+        }
+    }
+
+    // Picks up the session saved when the app was last closed, or starts
+    // from `cube` if there is none
+    pub fn restore(cube: RubiksCube, storage: Option<&dyn eframe::Storage>) -> Self {
+        MyApp::restore_with(cube, storage, false)
+    }
+
+    // Like `restore`, but with `keep_cube` the app starts from `cube` even
+    // if a session is saved, and only takes the settings from it. The moves
+    // of the session belong to its own cube, so they are dropped.
+    pub fn restore_with(
+        cube: RubiksCube,
+        storage: Option<&dyn eframe::Storage>,
+        keep_cube: bool,
+    ) -> Self {
+        let session: Option<Session> = storage
+            .and_then(|storage| storage.get_string(SESSION_KEY))
+            .and_then(|session| session.parse().ok());
+        let mut app = MyApp::new(cube.clone());
+        // User schemes have to be there before the session picks one
+        if Path::new(SCHEME_FILE).exists() {
+            app.load_schemes();
+        }
+        if let Some(session) = session {
+            app.apply_session(session);
+            if keep_cube {
+                app.animation.jump_to(&cube);
+                app.cube = cube;
+                app.history.clear();
+                app.redo.clear();
+            }
+        }
+        app
+    }
+
+    // Turns the cube without touching the history
+    fn turn(&mut self, m: Move) {
+        self.cube.apply_move(m);
+        self.animation.push(m);
+    }
+
+    pub fn apply_move(&mut self, m: Move) {
+        self.turn(m);
+        self.history.push(m);
+        self.redo.clear();
+    }
+
+    pub fn undo(&mut self) {
+        if let Some(m) = self.history.pop() {
+            self.turn(m.inverse());
+            self.redo.push(m);
+        }
+    }
+
+    pub fn redo(&mut self) {
+        if let Some(m) = self.redo.pop() {
+            self.turn(m);
+            self.history.push(m);
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.set_state(RubiksCube::new());
+    }
+
+    pub fn start_solve(&mut self) {
+        self.player = None;
+        self.solve_status = None;
+        self.solving = Some(PendingSolve {
+            job: SolveJob::spawn(self.cube.clone(), self.solver_settings),
+            cube: self.cube.clone(),
+        });
+    }
+
+    // Moves the solution player to `position`. The moves in between are
+    // added to the history so that it still leads to the shown state.
+    fn jump_to(&mut self, position: usize) {
+        let Some(player) = &mut self.player else {
+            return;
+        };
+        let moves = if position >= player.position() {
+            player.moves()[player.position()..position].to_vec()
+        } else {
+            invert_algorithm(&player.moves()[position..player.position()])
+        };
+        player.jump_to(position);
+        for m in moves {
+            self.cube.apply_move(m);
+            self.history.push(m);
+        }
+        self.redo.clear();
+        self.animation.jump_to(&self.cube);
+    }
+
+    fn update_solver(&mut self, ctx: &egui::Context) {
+        if let Some(pending) = &self.solving {
+            match pending.job.poll() {
+                Some(Ok(solution)) if pending.cube == self.cube => {
+                    self.solve_status = Some(Ok(format!(
+                        "{} moves in {:.2?}",
+                        solution.moves.len(),
+                        solution.elapsed
+                    )));
+                    self.player = Some(SolutionPlayer::new(self.cube.clone(), solution.moves));
+                    self.solving = None;
+                }
+                Some(Ok(_)) => {
+                    self.solve_status = Some(Err("The cube was turned while solving".to_string()));
+                    self.solving = None;
+                }
+                Some(Err(e)) => {
+                    self.solve_status = Some(Err(e));
+                    self.solving = None;
+                }
+                None => ctx.request_repaint_after(Duration::from_millis(100)),
+            }
+        }
+
+        // Any other turn leaves the solution behind
+        if self
+            .player
+            .as_ref()
+            .is_some_and(|player| player.cube() != &self.cube)
+        {
+            self.player = None;
+        }
+
+        // Play the next move once the previous one has been shown
+        let dt = ctx.input(|i| i.stable_dt);
+        let interval = 0.5 / self.animation.speed;
+        if let Some(player) = &mut self.player {
+            if player.playing {
+                ctx.request_repaint();
+            }
+            if self.animation.pending() == 0 {
+                if let Some(m) = player.update(dt, interval) {
+                    self.apply_move(m);
+                }
+            }
+        }
+    }
+
+    fn solver_controls(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Solve");
+        let settings = &mut self.solver_settings;
+        egui::ComboBox::from_label("Solver")
+            .selected_text(settings.solver.name())
+            .show_ui(ui, |ui| {
+                for solver in SolverChoice::ALL {
+                    ui.selectable_value(&mut settings.solver, solver, solver.name());
+                }
+            });
+        if settings.solver != SolverChoice::Bfs {
+            ui.add(egui::Slider::new(&mut settings.table_depth, TABLE_DEPTHS).text("table depth"));
+        }
+        if settings.solver == SolverChoice::Ida {
+            ui.add(egui::Slider::new(&mut settings.max_depth, MAX_DEPTHS).text("max depth"));
+        }
+
+        ui.horizontal(|ui| {
+            let idle = self.solving.is_none();
+            if ui
+                .add_enabled(idle && !self.cube.is_solved(), egui::Button::new("Solve"))
+                .clicked()
+            {
+                self.start_solve();
+            }
+            if !idle {
+                ui.spinner();
+                ui.label("Solving...");
+            }
+        });
+        match &self.solve_status {
+            Some(Ok(status)) => {
+                ui.label(status);
+            }
+            Some(Err(e)) => {
+                ui.colored_label(egui::Color32::RED, e);
+            }
+            None => {}
+        }
+
+        let Some(player) = &mut self.player else {
+            return;
+        };
+        let (len, position) = (player.moves().len(), player.position());
+        let mut jump = None;
+        let mut step = None;
+        ui.horizontal(|ui| {
+            if ui.button("⏮").on_hover_text("Back to the start").clicked() {
+                jump = Some(0);
+            }
+            if ui.button("◀").on_hover_text("Step back").clicked() {
+                step = player.step_back();
+            }
+            let play = if player.playing { "⏸" } else { "▶" };
+            if ui.button(play).on_hover_text("Play/pause").clicked() {
+                // Replay a finished solution from the start
+                if player.is_finished() {
+                    jump = Some(0);
+                }
+                player.playing = !player.playing;
+            }
+            if ui.button("▶|").on_hover_text("Step forward").clicked() {
+                step = player.step_forward();
+            }
+            if ui.button("⏭").on_hover_text("Jump to the end").clicked() {
+                jump = Some(len);
+            }
+        });
+
+        let mut slider_position = position;
+        if ui
+            .add(egui::Slider::new(&mut slider_position, 0..=len).text("move"))
+            .changed()
+        {
+            jump = Some(slider_position);
+        }
+        ui.horizontal_wrapped(|ui| {
+            for (i, m) in player.moves().iter().enumerate() {
+                // The move that led to the shown state is highlighted
+                if ui
+                    .selectable_label(position == i + 1, m.to_string())
+                    .clicked()
+                {
+                    jump = Some(i + 1);
+                }
+            }
+        });
+
+        if let Some(m) = step {
+            self.apply_move(m);
+        }
+        if let Some(position) = jump {
+            self.jump_to(position);
+        }
+    }
+
+    // Replaces the cube by an entered state, which has no moves leading to it
+    fn set_state(&mut self, cube: RubiksCube) {
+        self.cube = cube;
+        self.history.clear();
+        self.redo.clear();
+        self.animation.jump_to(&self.cube);
+    }
+
+    fn bookmark_controls(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Bookmarks");
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.bookmark_name);
+            if ui.button("Add").clicked() {
+                let name = match self.bookmark_name.trim() {
+                    "" => format!("Bookmark {}", self.bookmarks.len() + 1),
+                    name => name.to_string(),
+                };
+                self.bookmarks.push(Bookmark {
+                    name,
+                    cube: self.cube.clone(),
+                });
+                self.bookmark_name.clear();
+            }
+        });
+        let mut load = None;
+        let mut remove = None;
+        for (i, bookmark) in self.bookmarks.iter().enumerate() {
+            ui.horizontal(|ui| {
+                if ui.button("Load").clicked() {
+                    load = Some(bookmark.cube.clone());
+                }
+                if ui.button("✖").on_hover_text("Delete").clicked() {
+                    remove = Some(i);
+                }
+                ui.label(&bookmark.name);
+            });
+        }
+        if let Some(cube) = load {
+            self.set_state(cube);
+        }
+        if let Some(i) = remove {
+            self.bookmarks.remove(i);
+        }
+    }
+
+    fn editor_controls(&mut self, ui: &mut egui::Ui) {
+        let Some(editor) = &mut self.editor else {
+            return;
+        };
+        ui.heading("Edit stickers");
+        ui.label(
+            "Hold the cube with white on top and green in front. \
+             Click a sticker to paint it, right click to cycle its colour.",
+        );
+        ui.horizontal(|ui| {
+            for (color, count) in Color::ALL.into_iter().zip(editor.counts()) {
+                let button = egui::Button::new(
+                    RichText::new(count.to_string()).color(self.scheme.text_color(color)),
+                )
+                .fill(self.scheme.color(color))
+                .min_size(Vec2::splat(28.0))
+                .selected(editor.brush == color);
+                if ui
+                    .add(button)
+                    .on_hover_text("Stickers of this colour")
+                    .clicked()
+                {
+                    editor.brush = color;
+                }
+            }
+        });
+
+        let validation = editor.validate();
+        match &validation {
+            Ok(_) => {
+                ui.colored_label(Color32::DARK_GREEN, "Valid state");
+            }
+            Err(errors) => {
+                for e in errors {
+                    ui.colored_label(Color32::RED, e.to_string());
+                }
+            }
+        }
+
+        let valid = validation.is_ok();
+        let (mut solve, mut use_state, mut close) = (false, false, false);
+        ui.horizontal(|ui| {
+            solve = ui.add_enabled(valid, egui::Button::new("Solve")).clicked();
+            use_state = ui
+                .add_enabled(valid, egui::Button::new("Use state"))
+                .clicked();
+            if ui.button("Solved").clicked() {
+                editor.cube = RubiksCube::new();
+            }
+            close = ui.button("Cancel").clicked();
+        });
+        if solve || use_state {
+            let cube = editor.cube.clone();
+            self.set_state(cube);
+            close = true;
+        }
+        if close {
+            self.editor = None;
+        }
+        if solve {
+            self.start_solve();
+        }
+    }
+
+    fn open_trainer(&mut self) {
+        self.timer = None;
+        self.training = Some(Training {
+            trainer: Trainer::new(),
+            rng: StdRng::from_entropy(),
+            show_algorithm: false,
+        });
+        self.next_trainer_case();
+    }
+
+    fn next_trainer_case(&mut self) {
+        let Some(training) = &mut self.training else {
+            return;
+        };
+        training.show_algorithm = false;
+        if let Some(cube) = training
+            .trainer
+            .next_case(&mut training.rng, Instant::now())
+        {
+            self.set_state(cube);
+        }
+    }
+
+    // In perform mode the case is done as soon as the cube is solved
+    fn check_trainer(&mut self) {
+        let Some(training) = &mut self.training else {
+            return;
+        };
+        if training.trainer.mode == TrainerMode::Perform {
+            training.trainer.check(&self.cube, Instant::now());
+        }
+    }
+
+    fn trainer_controls(&mut self, ui: &mut egui::Ui) {
+        let Some(training) = &mut self.training else {
+            return;
+        };
+        let trainer = &mut training.trainer;
+        ui.heading("Trainer");
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut trainer.mode, TrainerMode::Recognize, "Name the case");
+            ui.selectable_value(&mut trainer.mode, TrainerMode::Perform, "Perform it");
+        });
+
+        let mut next = false;
+        if let Some(attempt) = trainer.current() {
+            match trainer.mode {
+                TrainerMode::Recognize => {
+                    ui.label("Which case is this?");
+                    let mut guess = None;
+                    ui.horizontal_wrapped(|ui| {
+                        for case in trainer.selection() {
+                            if ui.button(trainer.cases()[case].label()).clicked() {
+                                guess = Some(case);
+                            }
+                        }
+                    });
+                    if let Some(case) = guess {
+                        trainer.guess(case, Instant::now());
+                        next = true;
+                    }
+                }
+                TrainerMode::Perform => {
+                    ui.label("Solve the case with the moves below");
+                    ui.checkbox(&mut training.show_algorithm, "Show algorithm");
+                    if training.show_algorithm {
+                        let case = trainer.cases()[attempt.case];
+                        ui.label(format!("{}: {}", case.label(), case.algorithm));
+                    }
+                    if ui.button("Give up").clicked() {
+                        trainer.give_up(Instant::now());
+                    }
+                }
+            }
+        } else {
+            next = ui
+                .add_enabled(
+                    !trainer.selection().is_empty(),
+                    egui::Button::new("Next case"),
+                )
+                .clicked();
+        }
+
+        if let Some(answer) = trainer.last_answer() {
+            let case = trainer.cases()[answer.case];
+            let time = format_result(Some(answer.time));
+            if answer.correct {
+                ui.colored_label(
+                    Color32::DARK_GREEN,
+                    format!("Correct: {} in {} s", case.label(), time),
+                );
+            } else {
+                ui.colored_label(
+                    Color32::RED,
+                    format!("Wrong: it was {} ({})", case.label(), case.algorithm),
+                );
+            }
+        }
+
+        egui::CollapsingHeader::new("Cases").show(ui, |ui| {
+            for set in CaseSet::ALL {
+                ui.horizontal(|ui| {
+                    ui.strong(set.name());
+                    if ui.small_button("all").clicked() {
+                        trainer.select_set(set, true);
+                    }
+                    if ui.small_button("none").clicked() {
+                        trainer.select_set(set, false);
+                    }
+                });
+                ui.horizontal_wrapped(|ui| {
+                    for i in 0..trainer.cases().len() {
+                        let case = trainer.cases()[i];
+                        if case.set == set {
+                            ui.checkbox(&mut trainer.selected[i], case.name);
+                        }
+                    }
+                });
+            }
+        });
+
+        egui::CollapsingHeader::new("Statistics").show(ui, |ui| {
+            egui::Grid::new("trainer_stats")
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong("case");
+                    ui.strong("correct");
+                    ui.strong("accuracy");
+                    ui.strong("mean time");
+                    ui.end_row();
+                    for (i, case) in trainer.cases().iter().enumerate() {
+                        let stats = trainer.stats(i);
+                        let Some(accuracy) = stats.accuracy() else {
+                            continue;
+                        };
+                        ui.label(case.label());
+                        ui.label(format!("{}/{}", stats.correct, stats.attempts));
+                        ui.label(format!("{:.0}%", accuracy * 100.0));
+                        ui.label(
+                            stats
+                                .mean_time()
+                                .map_or("-".to_string(), |t| format_result(Some(t))),
+                        );
+                        ui.end_row();
+                    }
+                });
+            if ui.button("Reset statistics").clicked() {
+                trainer.reset_stats();
+            }
+        });
+
+        if next {
+            self.next_trainer_case();
+        }
+    }
+
+    fn open_timer(&mut self) {
+        self.training = None;
+        let mut rng = StdRng::from_entropy();
+        self.timer = Some(TimerMode {
+            timer: Timer::default(),
+            session: TimerSession::default(),
+            scramble: generate_scramble(&mut rng, SCRAMBLE_LENGTH),
+            rng,
+            export_path: "data/timer_session.csv".to_string(),
+            export_status: None,
+        });
+        self.show_timer_scramble();
+    }
+
+    // Puts the scramble of the next solve on the cube
+    fn show_timer_scramble(&mut self) {
+        let Some(mode) = &self.timer else {
+            return;
+        };
+        let scramble = mode.scramble.clone();
+        self.set_state(RubiksCube::new());
+        for m in scramble {
+            self.apply_move(m);
+        }
+        self.animation.jump_to(&self.cube);
+    }
+
+    fn next_timer_scramble(&mut self) {
+        if let Some(mode) = &mut self.timer {
+            mode.scramble = generate_scramble(&mut mode.rng, SCRAMBLE_LENGTH);
+        }
+        self.show_timer_scramble();
+    }
+
+    fn handle_timer_keys(&mut self, ctx: &egui::Context) {
+        let Some(mode) = &mut self.timer else {
+            return;
+        };
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        // The spacebar would also click the focused button
+        ctx.memory_mut(|memory| {
+            if let Some(id) = memory.focused() {
+                memory.surrender_focus(id);
+            }
+        });
+        let (pressed, released) =
+            ctx.input(|i| (i.key_pressed(Key::Space), i.key_released(Key::Space)));
+        let now = Instant::now();
+        let mut finished = false;
+        if pressed {
+            if let Some((time, penalty)) = mode.timer.space_pressed(now) {
+                mode.session.solves.push(Solve {
+                    scramble: mode.scramble.clone(),
+                    time,
+                    penalty,
+                });
+                finished = true;
+            }
+        }
+        if released {
+            mode.timer.space_released(now);
+        }
+        if mode.timer.is_active() {
+            ctx.request_repaint();
+        }
+        if finished {
+            self.next_timer_scramble();
+        }
+    }
+
+    fn timer_view(&self, ui: &mut egui::Ui) {
+        let Some(mode) = &self.timer else {
+            return;
+        };
+        let now = Instant::now();
+        ui.label(
+            RichText::new(algorithm_to_string(&mode.scramble))
+                .monospace()
+                .size(20.0),
+        );
+        let (time, color, hint) = match mode.timer.state {
+            TimerState::Inspecting { .. } => {
+                let left = mode.timer.inspection_left(now).unwrap_or(0.0);
+                let time = if left > 0.0 {
+                    format!("{}", left.ceil())
+                } else if left > -2.0 {
+                    "+2".to_string()
+                } else {
+                    "DNF".to_string()
+                };
+                (
+                    time,
+                    Color32::from_rgb(255, 140, 0),
+                    "Release space to start",
+                )
+            }
+            TimerState::Running { .. } => (
+                format_result(mode.timer.elapsed(now)),
+                ui.visuals().strong_text_color(),
+                "Press space to stop",
+            ),
+            TimerState::Idle | TimerState::Stopped => (
+                mode.session
+                    .solves
+                    .last()
+                    .map_or("0.00".to_string(), |solve| format_result(solve.result())),
+                ui.visuals().text_color(),
+                if mode.timer.inspection {
+                    "Release space to start inspection"
+                } else {
+                    "Release space to start"
+                },
+            ),
+        };
+        ui.label(RichText::new(time).size(64.0).monospace().color(color));
+        ui.label(hint);
+    }
+
+    fn timer_controls(&mut self, ui: &mut egui::Ui) {
+        let Some(mode) = &mut self.timer else {
+            return;
+        };
+        ui.heading("Timer");
+        ui.add_enabled(
+            !mode.timer.is_active(),
+            egui::Checkbox::new(&mut mode.timer.inspection, "15 s inspection"),
+        );
+
+        let session = &mut mode.session;
+        let average = |n| match session.average_of(n) {
+            Some(average) => format_result(average),
+            None => "-".to_string(),
+        };
+        egui::Grid::new("timer_stats").show(ui, |ui| {
+            ui.label("solves");
+            ui.label(session.solves.len().to_string());
+            ui.end_row();
+            ui.label("best");
+            ui.label(
+                session
+                    .best()
+                    .map_or("-".to_string(), |t| format_result(Some(t))),
+            );
+            ui.end_row();
+            ui.label("mean");
+            ui.label(
+                session
+                    .mean()
+                    .map_or("-".to_string(), |t| format_result(Some(t))),
+            );
+            ui.end_row();
+            ui.label("ao5");
+            ui.label(average(5));
+            ui.end_row();
+            ui.label("ao12");
+            ui.label(average(12));
+            ui.end_row();
+        });
+
+        if let Some(last) = session.solves.last_mut() {
+            ui.horizontal(|ui| {
+                ui.label("Last solve:");
+                for penalty in [Penalty::None, Penalty::PlusTwo, Penalty::Dnf] {
+                    let name = if penalty == Penalty::None {
+                        "OK"
+                    } else {
+                        penalty.name()
+                    };
+                    ui.selectable_value(&mut last.penalty, penalty, name);
+                }
+            });
+        }
+        let mut delete_last = false;
+        ui.horizontal(|ui| {
+            delete_last = ui
+                .add_enabled(!session.solves.is_empty(), egui::Button::new("Delete last"))
+                .clicked();
+        });
+        if delete_last {
+            session.solves.pop();
+        }
+        ui.horizontal_wrapped(|ui| {
+            for solve in session.solves.iter().rev().take(12) {
+                ui.label(format_result(solve.result()));
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut mode.export_path);
+            if ui.button("Export CSV").clicked() {
+                mode.export_status = Some(
+                    mode.session
+                        .write_csv(&mode.export_path)
+                        .map(|_| format!("Saved to {}", mode.export_path))
+                        .map_err(|e| e.to_string()),
+                );
+            }
+        });
+        match &mode.export_status {
+            Some(Ok(status)) => {
+                ui.label(status);
+            }
+            Some(Err(e)) => {
+                ui.colored_label(Color32::RED, e);
+            }
+            None => {}
+        }
+        if ui.button("New scramble").clicked() {
+            self.next_timer_scramble();
+        }
+    }
+
+    // Face turns from the keyboard: U D F B R L turn clockwise,
+    // with Shift counterclockwise and with Alt by 180 degrees
+    fn handle_keyboard(&mut self, ctx: &egui::Context) {
+        // Typing into the scramble box must not turn the cube
+        if ctx.wants_keyboard_input() || self.editor.is_some() {
+            return;
+        }
+        let keys = [
+            (Key::U, Face::U),
+            (Key::D, Face::D),
+            (Key::F, Face::F),
+            (Key::B, Face::B),
+            (Key::R, Face::R),
+            (Key::L, Face::L),
+        ];
+        let (undo, redo) = ctx.input(|i| {
+            let command = i.modifiers.command;
+            let undo = command && !i.modifiers.shift && i.key_pressed(Key::Z);
+            let redo =
+                command && (i.key_pressed(Key::Y) || i.modifiers.shift && i.key_pressed(Key::Z));
+            (undo, redo)
+        });
+        if undo {
+            self.undo();
+        }
+        if redo {
+            self.redo();
+        }
+        let moves: Vec<Move> = ctx.input(|i| {
+            if i.modifiers.command {
+                return Vec::new();
+            }
+            keys.iter()
+                .filter(|(key, _)| i.key_pressed(*key))
+                .map(|&(_, face)| {
+                    let turn = if i.modifiers.alt {
+                        Turn::Double
+                    } else if i.modifiers.shift {
+                        Turn::CounterClockwise
+                    } else {
+                        Turn::Clockwise
+                    };
+                    Move::new(face, turn)
+                })
+                .collect()
+        });
+        for m in moves {
+            self.apply_move(m);
+        }
+    }
+
+    fn controls(&mut self, ui: &mut egui::Ui) {
+        if self.editor.is_some() {
+            self.editor_controls(ui);
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.view, View::Net, "Net");
+            ui.selectable_value(&mut self.view, View::Perspective, "3D");
+            let mut timer_open = self.timer.is_some();
+            if ui.toggle_value(&mut timer_open, "Timer").clicked() {
+                if timer_open {
+                    self.open_timer();
+                } else {
+                    self.timer = None;
+                }
+            }
+            let mut trainer_open = self.training.is_some();
+            if ui.toggle_value(&mut trainer_open, "Trainer").clicked() {
+                if trainer_open {
+                    self.open_trainer();
+                } else {
+                    self.training = None;
+                }
+            }
+        });
+        if self.view == View::Perspective {
+            ui.label("Drag a face to turn it, drag elsewhere or with the right button to orbit");
+        }
+
+        if self.timer.is_some() {
+            ui.separator();
+            self.timer_controls(ui);
+        }
+        if self.training.is_some() {
+            ui.separator();
+            self.trainer_controls(ui);
+        }
+
+        ui.separator();
+        ui.heading("Moves");
+        egui::Grid::new("move_buttons").show(ui, |ui| {
+            for face in Face::ALL {
+                for turn in Turn::ALL {
+                    let m = Move::new(face, turn);
+                    if ui.button(m.to_string()).clicked() {
+                        self.apply_move(m);
+                    }
+                }
+                ui.end_row();
+            }
+        });
+        ui.label("Keys: U D F B R L, Shift for prime, Alt for double");
+
+        ui.separator();
+        ui.heading("Scramble");
+        let response = ui.text_edit_singleline(&mut self.scramble_input);
+        let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter));
+        if ui.button("Apply").clicked() || submitted {
+            match parse_algorithm(&self.scramble_input) {
+                Ok(moves) => {
+                    for m in moves {
+                        self.apply_move(m);
+                    }
+                    self.scramble_error = None;
+                }
+                Err(e) => self.scramble_error = Some(e),
+            }
+        }
+        if let Some(error) = &self.scramble_error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+
+        ui.separator();
+        self.scheme_controls(ui);
+
+        ui.separator();
+        ui.heading("Animation");
+        if ui
+            .checkbox(&mut self.animation.enabled, "Animate turns")
+            .changed()
+        {
+            self.animation.jump_to(&self.cube);
+        }
+        ui.add(egui::Slider::new(&mut self.animation.speed, SPEEDS).text("quarter turns/s"));
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(!self.history.is_empty(), egui::Button::new("Undo"))
+                .clicked()
+            {
+                self.undo();
+            }
+            if ui
+                .add_enabled(!self.redo.is_empty(), egui::Button::new("Redo"))
+                .clicked()
+            {
+                self.redo();
+            }
+            if ui.button("Reset").clicked() {
+                self.reset();
+            }
+            if ui.button("Edit stickers").clicked() {
+                self.editor = Some(StateEditor::new(self.cube.clone()));
+            }
+        });
+
+        ui.label("Ctrl+Z undoes, Ctrl+Y or Ctrl+Shift+Z redoes");
+
+        ui.separator();
+        self.solver_controls(ui);
+
+        ui.separator();
+        self.bookmark_controls(ui);
+
+        ui.separator();
+        ui.heading(format!("History ({} moves)", self.history.len()));
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.add(egui::Label::new(algorithm_to_string(&self.history)).wrap());
+        });
     }
 }
 
 impl eframe::App for MyApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        storage.set_string(SESSION_KEY, self.session().to_string());
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        egui::CentralPanel::default().show(ctx, |ui| {
-            let available_size = ui.available_size();
-            let square_size = (available_size.x.min(available_size.y) / 12.0).max(20.0); // Adjust square size dynamically
+        self.handle_keyboard(ctx);
+        self.handle_timer_keys(ctx);
+        self.check_trainer();
+        if self.animation.update(ctx.input(|i| i.stable_dt)) {
+            ctx.request_repaint();
+        }
+        self.update_solver(ctx);
 
-            let cube_size = egui::Vec2::new(9.0 * square_size, 6.0 * square_size);
-            if available_size.x < cube_size.x || available_size.y < cube_size.y {
-                ui.label("Not enough space to render the cube! Resize the window.");
+        egui::SidePanel::right("controls").show(ctx, |ui| {
+            self.controls(ui);
+        });
+
+        if self.timer.is_some() && self.editor.is_none() {
+            egui::TopBottomPanel::top("timer").show(ctx, |ui| {
+                ui.vertical_centered(|ui| self.timer_view(ui));
+            });
+        }
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            if self.editor.is_some() {
+                self.editor_view(ui);
                 return;
             }
+            match self.view {
+                View::Net => draw_net_view(&self.animation, &self.scheme, ui),
+                View::Perspective => self.perspective_view(ui),
+            }
+        });
+    }
+}
 
-            let top_left = (available_size - cube_size) / 2.0;
-            let top_left = ui.min_rect().min + top_left;
+impl MyApp {
+    fn perspective_view(&mut self, ui: &mut egui::Ui) {
+        let (response, painter) = ui.allocate_painter(ui.available_size(), Sense::click_and_drag());
+        let rect = response.rect;
 
-            draw_cube(&self.cube, ui.painter(), top_left, square_size);
-        });
+        if response.drag_started() {
+            let origin = ui.input(|i| i.pointer.press_origin());
+            self.drag = match origin.and_then(|pos| sticker_under(&self.camera, rect, pos)) {
+                // The centre sticker gives no direction to turn in
+                Some((face, index))
+                    if index != 4 && response.dragged_by(PointerButton::Primary) =>
+                {
+                    origin.map(|start| Drag::Turn { face, start })
+                }
+                _ => Some(Drag::Orbit),
+            };
+        }
+        if self.drag == Some(Drag::Orbit) {
+            self.camera.orbit(response.drag_delta());
+        }
+        if response.drag_stopped() {
+            if let (Some(Drag::Turn { face, start }), Some(end)) =
+                (self.drag, response.interact_pointer_pos())
+            {
+                if let Some(m) = drag_turn(&self.camera, rect, face, start, end) {
+                    self.apply_move(m);
+                }
+            }
+            self.drag = None;
+        }
+
+        let highlight = match self.drag {
+            Some(Drag::Turn { face, .. }) => Some(face),
+            _ => response
+                .hover_pos()
+                .and_then(|pos| sticker_under(&self.camera, rect, pos))
+                .map(|(face, _)| face),
+        };
+        draw_cube_3d(
+            self.animation.shown(),
+            &self.scheme,
+            &painter,
+            rect,
+            &self.camera,
+            highlight,
+            self.animation.current(),
+        );
     }
 }
 
-pub fn draw_cube(cube: &RubiksCube, painter: &Painter, top_left: Pos2, square_size: f32) {
-    let face_positions = [
-        (0, -1), // Top
-        (0, 1),  // Bottom
-        (0, 0),  // Front
-        (2, 0),  // Back
-        (1, 0),  // Right
-        (-1, 0), // Left
-    ];
+impl MyApp {
+    fn editor_view(&mut self, ui: &mut egui::Ui) {
+        let Some(editor) = &mut self.editor else {
+            return;
+        };
+        let Some((top_left, square_size)) = net_layout(ui) else {
+            return;
+        };
+        let response = ui.interact(ui.max_rect(), ui.id().with("editor"), Sense::click());
+        let clicked = response
+            .interact_pointer_pos()
+            .and_then(|pos| net_sticker_at(pos, top_left, square_size));
+        if let Some((face, index)) = clicked {
+            if response.clicked() {
+                editor.paint(face, index);
+            } else if response.secondary_clicked() {
+                editor.cycle(face, index);
+            }
+        }
 
-    for (face_idx, &(dx, dy)) in face_positions.iter().enumerate() {
-        let face = &cube.faces[face_idx];
-        for y in 0..3 {
-            for x in 0..3 {
-                let color = face[y * 3 + x].to_color32();
-                let rect = Rect::from_min_size(
-                    top_left
-                        + Vec2::new((dx * 3 + x as i32) as f32, (dy * 3 + y as i32) as f32)
-                            * square_size,
-                    Vec2::splat(square_size),
-                );
-                painter.rect_filled(rect, 0.0, color);
+        let painter = ui.painter();
+        draw_cube(&editor.cube, &self.scheme, painter, top_left, square_size);
+        for face in 0..6 {
+            for index in 0..9 {
+                let rect = sticker_rect(face, index, top_left, square_size);
+                painter.rect_stroke(rect, 0.0, Stroke::new(1.0, Color32::BLACK));
             }
+            // Centres can't be edited
+            let center = sticker_rect(face, 4, top_left, square_size).center();
+            painter.circle_stroke(center, square_size * 0.15, Stroke::new(2.0, Color32::BLACK));
+        }
+    }
+}
+
+// Top left corner and sticker size of the net in the available space
+fn net_layout(ui: &mut egui::Ui) -> Option<(Pos2, f32)> {
+    let available_size = ui.available_size();
+    let square_size = (available_size.x.min(available_size.y) / 12.0).max(20.0); // Adjust square size dynamically
+
+    let cube_size = egui::Vec2::new(9.0 * square_size, 6.0 * square_size);
+    if available_size.x < cube_size.x || available_size.y < cube_size.y {
+        ui.label("Not enough space to render the cube! Resize the window.");
+        return None;
+    }
+
+    let top_left = (available_size - cube_size) / 2.0;
+    Some((ui.min_rect().min + top_left, square_size))
+}
+
+fn draw_net_view(animation: &Animation, scheme: &ColorScheme, ui: &mut egui::Ui) {
+    let Some((top_left, square_size)) = net_layout(ui) else {
+        return;
+    };
+
+    match animation.current() {
+        Some((m, progress)) => draw_cube_turning(
+            animation.shown(),
+            scheme,
+            m,
+            progress,
+            ui.painter(),
+            top_left,
+            square_size,
+        ),
+        None => draw_cube(
+            animation.shown(),
+            scheme,
+            ui.painter(),
+            top_left,
+            square_size,
+        ),
+    }
+}
+
+fn sticker_rect(face: usize, index: usize, top_left: Pos2, square_size: f32) -> Rect {
+    let (x, y) = net_sticker_offset(face, index);
+    Rect::from_min_size(
+        top_left + Vec2::new(x as f32, y as f32) * square_size,
+        Vec2::splat(square_size),
+    )
+}
+
+// Face and index of the sticker of the net at `pos`
+fn net_sticker_at(pos: Pos2, top_left: Pos2, square_size: f32) -> Option<(usize, usize)> {
+    (0..6)
+        .flat_map(|face| (0..9).map(move |index| (face, index)))
+        .find(|&(face, index)| sticker_rect(face, index, top_left, square_size).contains(pos))
+}
+
+// Writes the letter of `color` at `center` if the scheme shows letters
+fn draw_letter(
+    painter: &Painter,
+    scheme: &ColorScheme,
+    color: Color,
+    center: Pos2,
+    square_size: f32,
+) {
+    if scheme.show_letters {
+        painter.text(
+            center,
+            Align2::CENTER_CENTER,
+            scheme.letter(color),
+            FontId::proportional(square_size * 0.5),
+            scheme.text_color(color),
+        );
+    }
+}
+
+pub fn draw_cube(
+    cube: &RubiksCube,
+    scheme: &ColorScheme,
+    painter: &Painter,
+    top_left: Pos2,
+    square_size: f32,
+) {
+    for (face_idx, face) in cube.faces.iter().enumerate() {
+        for (i, &color) in face.iter().enumerate() {
+            let rect = sticker_rect(face_idx, i, top_left, square_size);
+            painter.rect_filled(rect, 0.0, scheme.color(color));
+            draw_letter(painter, scheme, color, rect.center(), square_size);
+        }
+    }
+}
+
+// Draws `cube` while `m` is played, `progress` of the way: the turning face
+// rotates and the stickers carried onto the neighbouring faces fade into
+// their new colours
+pub fn draw_cube_turning(
+    cube: &RubiksCube,
+    scheme: &ColorScheme,
+    m: Move,
+    progress: f32,
+    painter: &Painter,
+    top_left: Pos2,
+    square_size: f32,
+) {
+    let mut after = cube.clone();
+    after.apply_move(m);
+    for (face_idx, face) in cube.faces.iter().enumerate() {
+        if face_idx == m.face.index() {
+            continue;
+        }
+        for (i, &color) in face.iter().enumerate() {
+            let rect = sticker_rect(face_idx, i, top_left, square_size);
+            let next = after.faces[face_idx][i];
+            let fill = scheme
+                .color(color)
+                .lerp_to_gamma(scheme.color(next), progress);
+            painter.rect_filled(rect, 0.0, fill);
+            let letter = if progress < 0.5 { color } else { next };
+            draw_letter(painter, scheme, letter, rect.center(), square_size);
         }
     }
+
+    // Screen y points down, so a positive angle is clockwise
+    let angle = progress
+        * match m.turn {
+            Turn::Clockwise => FRAC_PI_2,
+            Turn::Double => PI,
+            Turn::CounterClockwise => -FRAC_PI_2,
+        };
+    let (sin, cos) = angle.sin_cos();
+    let center = sticker_rect(m.face.index(), 4, top_left, square_size).center();
+    let rotate = |p: Pos2| {
+        let d = p - center;
+        center + Vec2::new(d.x * cos - d.y * sin, d.x * sin + d.y * cos)
+    };
+    for (i, &color) in cube.faces[m.face.index()].iter().enumerate() {
+        let rect = sticker_rect(m.face.index(), i, top_left, square_size);
+        let points = [
+            rect.left_top(),
+            rect.right_top(),
+            rect.right_bottom(),
+            rect.left_bottom(),
+        ]
+        .map(rotate)
+        .to_vec();
+        painter.add(Shape::convex_polygon(
+            points,
+            scheme.color(color),
+            Stroke::NONE,
+        ));
+        draw_letter(painter, scheme, color, rotate(rect.center()), square_size);
+    }
 }
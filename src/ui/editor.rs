@@ -0,0 +1,56 @@
+//! Entering the stickers of a physical cube by hand.
+use crate::rubiks::color::Color;
+use crate::rubiks::cube::RubiksCube;
+use crate::rubiks::cubie::{CubieCube, StateError};
+
+pub struct StateEditor {
+    pub cube: RubiksCube,
+    // Colour that clicked stickers are painted with
+    pub brush: Color,
+}
+
+impl StateEditor {
+    pub fn new(cube: RubiksCube) -> Self {
+        StateEditor {
+            cube,
+            brush: Color::W,
+        }
+    }
+
+    // Centres fix the orientation of the cube, so they can't be edited.
+    // Returns whether the sticker changed.
+    pub fn paint(&mut self, face: usize, index: usize) -> bool {
+        if index == 4 || self.cube.faces[face][index] == self.brush {
+            return false;
+        }
+        self.cube.faces[face][index] = self.brush;
+        true
+    }
+
+    // Gives the sticker the next colour in Color::ALL
+    pub fn cycle(&mut self, face: usize, index: usize) -> bool {
+        if index == 4 {
+            return false;
+        }
+        let color = self.cube.faces[face][index];
+        let next = Color::ALL.iter().position(|&c| c == color).unwrap() + 1;
+        self.cube.faces[face][index] = Color::ALL[next % Color::ALL.len()];
+        true
+    }
+
+    // Number of stickers of every colour in Color::ALL
+    pub fn counts(&self) -> [usize; 6] {
+        Color::ALL.map(|color| {
+            self.cube
+                .faces
+                .iter()
+                .flatten()
+                .filter(|&&c| c == color)
+                .count()
+        })
+    }
+
+    pub fn validate(&self) -> Result<CubieCube, Vec<StateError>> {
+        self.cube.validate()
+    }
+}
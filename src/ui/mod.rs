@@ -1 +1,9 @@
+pub mod animation;
 pub mod app;
+pub mod editor;
+pub mod player;
+pub mod scheme;
+pub mod session;
+pub mod timer;
+pub mod trainer;
+pub mod view3d;
@@ -0,0 +1,195 @@
+//! Solving in the background and stepping through the solution.
+use crate::rubiks::astar::AStarConfig;
+use crate::rubiks::cube::RubiksCube;
+use crate::rubiks::moves::{parse_algorithm, Move};
+use crate::rubiks::table::DistanceTable;
+use std::ops::RangeInclusive;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SolverChoice {
+    Bfs,
+    Ida,
+    AStar,
+}
+
+impl SolverChoice {
+    pub const ALL: [SolverChoice; 3] = [SolverChoice::Bfs, SolverChoice::Ida, SolverChoice::AStar];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SolverChoice::Bfs => "BFS",
+            SolverChoice::Ida => "IDA*",
+            SolverChoice::AStar => "Weighted A*",
+        }
+    }
+}
+
+// Depths the solver settings can be set to
+pub const TABLE_DEPTHS: RangeInclusive<usize> = 1..=6;
+pub const MAX_DEPTHS: RangeInclusive<usize> = 1..=20;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SolverSettings {
+    pub solver: SolverChoice,
+    // Depth of the distance table used as heuristic by IDA* and A*
+    pub table_depth: usize,
+    // IDA* gives up beyond this many moves
+    pub max_depth: usize,
+}
+
+impl Default for SolverSettings {
+    fn default() -> Self {
+        SolverSettings {
+            solver: SolverChoice::Ida,
+            table_depth: 5,
+            max_depth: 14,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    pub moves: Vec<Move>,
+    pub elapsed: Duration,
+}
+
+// Runs the chosen solver, blocking until it is done
+pub fn solve(cube: &RubiksCube, settings: &SolverSettings) -> Result<Solution, String> {
+    let start = Instant::now();
+    let solution = match settings.solver {
+        SolverChoice::Bfs => cube.solve(),
+        SolverChoice::Ida => cube
+            .solve_ida(
+                &DistanceTable::new(settings.table_depth),
+                settings.max_depth,
+            )
+            .map(|result| result.solution),
+        SolverChoice::AStar => cube
+            .solve_weighted_astar(
+                &DistanceTable::new(settings.table_depth),
+                &AStarConfig::default(),
+            )
+            .map(|result| result.solution),
+    };
+    let solution =
+        solution.ok_or_else(|| format!("{} found no solution", settings.solver.name()))?;
+    Ok(Solution {
+        moves: parse_algorithm(&solution.join(" "))?,
+        elapsed: start.elapsed(),
+    })
+}
+
+// A solver running on its own thread so that the window stays responsive
+pub struct SolveJob {
+    receiver: Receiver<Result<Solution, String>>,
+}
+
+impl SolveJob {
+    pub fn spawn(cube: RubiksCube, settings: SolverSettings) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            // The receiver is gone if the job was cancelled
+            let _ = sender.send(solve(&cube, &settings));
+        });
+        SolveJob { receiver }
+    }
+
+    // The result once the solver has finished
+    pub fn poll(&self) -> Option<Result<Solution, String>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err("the solver crashed".to_string())),
+        }
+    }
+}
+
+// Timeline over the states of a solution
+pub struct SolutionPlayer {
+    start: RubiksCube,
+    moves: Vec<Move>,
+    // Number of moves applied to `start` to get `cube`
+    position: usize,
+    cube: RubiksCube,
+    pub playing: bool,
+    // Seconds since the last step while playing
+    elapsed: f32,
+}
+
+impl SolutionPlayer {
+    pub fn new(start: RubiksCube, moves: Vec<Move>) -> Self {
+        SolutionPlayer {
+            cube: start.clone(),
+            start,
+            moves,
+            position: 0,
+            playing: false,
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    // State after the first `position` moves
+    pub fn cube(&self) -> &RubiksCube {
+        &self.cube
+    }
+
+    // The move played last, which the timeline highlights
+    pub fn current_move(&self) -> Option<Move> {
+        self.position.checked_sub(1).map(|i| self.moves[i])
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.position == self.moves.len()
+    }
+
+    // Plays the next move and returns it
+    pub fn step_forward(&mut self) -> Option<Move> {
+        let m = *self.moves.get(self.position)?;
+        self.cube.apply_move(m);
+        self.position += 1;
+        Some(m)
+    }
+
+    // Undoes the last move and returns the move that undoes it
+    pub fn step_back(&mut self) -> Option<Move> {
+        let m = self.current_move()?.inverse();
+        self.cube.apply_move(m);
+        self.position -= 1;
+        Some(m)
+    }
+
+    pub fn jump_to(&mut self, position: usize) {
+        self.position = position.min(self.moves.len());
+        self.cube = self.start.clone();
+        self.cube.apply_moves(&self.moves[..self.position]);
+    }
+
+    // Advances playback by `dt` seconds and plays the next move once
+    // `interval` seconds have passed since the last one
+    pub fn update(&mut self, dt: f32, interval: f32) -> Option<Move> {
+        if !self.playing {
+            return None;
+        }
+        if self.is_finished() {
+            self.playing = false;
+            return None;
+        }
+        self.elapsed += dt;
+        if self.elapsed < interval {
+            return None;
+        }
+        self.elapsed = 0.0;
+        self.step_forward()
+    }
+}
@@ -0,0 +1,231 @@
+//! Colours the stickers are drawn in.
+//!
+//! A scheme gives every sticker colour of the model (the colour of the face
+//! it belongs to on the solved cube) the colour it is drawn in and a letter
+//! that can be written on it. Besides the built-in schemes, schemes can be
+//! defined in a text file:
+//!
+//! ```text
+//! # Lines starting with # are comments
+//! scheme Pastel
+//! W #ffffff
+//! Y #fdfd96 Y
+//! G #77dd77 G
+//! B #779ecb B
+//! R #ff6961 R
+//! O #ffb347 O
+//! ```
+//!
+//! Every scheme lists the six model colours W Y G B R O with a hex colour
+//! and optionally the letter to overlay, which defaults to the model colour.
+use crate::rubiks::color::Color;
+use eframe::egui::Color32;
+use std::fs;
+use std::path::Path;
+
+// Where the visualizer looks for user defined schemes
+pub const SCHEME_FILE: &str = "color_schemes.txt";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColorScheme {
+    pub name: String,
+    // Indexed like Color::ALL
+    pub colors: [Color32; 6],
+    pub letters: [char; 6],
+    // Write the letters on the stickers
+    pub show_letters: bool,
+}
+
+fn index(color: Color) -> usize {
+    Color::ALL
+        .iter()
+        .position(|&c| c == color)
+        .expect("Color::ALL has every colour")
+}
+
+impl ColorScheme {
+    fn new(name: &str, colors: [(u8, u8, u8); 6], letters: [char; 6]) -> Self {
+        ColorScheme {
+            name: name.to_string(),
+            colors: colors.map(|(r, g, b)| Color32::from_rgb(r, g, b)),
+            letters,
+            show_letters: false,
+        }
+    }
+
+    // The colours of an official cube: white opposite yellow, green
+    // opposite blue and red opposite orange
+    pub fn standard() -> Self {
+        ColorScheme::new(
+            "Standard",
+            [
+                (255, 255, 255),
+                (255, 213, 0),
+                (0, 155, 72),
+                (0, 69, 173),
+                (183, 18, 52),
+                (255, 88, 0),
+            ],
+            ['W', 'Y', 'G', 'B', 'R', 'O'],
+        )
+    }
+
+    // White opposite blue and yellow opposite green
+    pub fn japanese() -> Self {
+        ColorScheme::new(
+            "Japanese",
+            [
+                (255, 255, 255),
+                (0, 69, 173),
+                (0, 155, 72),
+                (255, 213, 0),
+                (183, 18, 52),
+                (255, 88, 0),
+            ],
+            ['W', 'B', 'G', 'Y', 'R', 'O'],
+        )
+    }
+
+    // Saturated colours that differ strongly in brightness
+    pub fn high_contrast() -> Self {
+        ColorScheme::new(
+            "High contrast",
+            [
+                (255, 255, 255),
+                (255, 240, 0),
+                (0, 200, 0),
+                (0, 40, 220),
+                (200, 0, 0),
+                (255, 140, 0),
+            ],
+            ['W', 'Y', 'G', 'B', 'R', 'O'],
+        )
+    }
+
+    // The Okabe-Ito palette, which stays distinguishable with the common
+    // kinds of colour blindness, with the letters shown
+    pub fn color_blind() -> Self {
+        ColorScheme {
+            show_letters: true,
+            ..ColorScheme::new(
+                "Colour-blind safe",
+                [
+                    (255, 255, 255),
+                    (240, 228, 66),
+                    (0, 158, 115),
+                    (0, 114, 178),
+                    (213, 94, 0),
+                    (230, 159, 0),
+                ],
+                ['W', 'Y', 'G', 'B', 'R', 'O'],
+            )
+        }
+    }
+
+    pub fn builtin() -> Vec<ColorScheme> {
+        vec![
+            ColorScheme::standard(),
+            ColorScheme::japanese(),
+            ColorScheme::high_contrast(),
+            ColorScheme::color_blind(),
+        ]
+    }
+
+    pub fn color(&self, color: Color) -> Color32 {
+        self.colors[index(color)]
+    }
+
+    pub fn letter(&self, color: Color) -> char {
+        self.letters[index(color)]
+    }
+
+    // Black or white, whichever is readable on the sticker
+    pub fn text_color(&self, color: Color) -> Color32 {
+        let [r, g, b, _] = self.color(color).to_array();
+        let luma = 0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32;
+        if luma > 140.0 {
+            Color32::BLACK
+        } else {
+            Color32::WHITE
+        }
+    }
+}
+
+impl Default for ColorScheme {
+    fn default() -> Self {
+        ColorScheme::standard()
+    }
+}
+
+fn parse_hex(hex: &str) -> Option<Color32> {
+    let hex = hex.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    Some(Color32::from_rgb(channel(0)?, channel(2)?, channel(4)?))
+}
+
+// Reads schemes in the format described at the top of this file
+pub fn parse_schemes(text: &str) -> Result<Vec<ColorScheme>, String> {
+    let mut schemes = Vec::new();
+    // The scheme being read, with the colours it has so far
+    let mut current: Option<(ColorScheme, [bool; 6])> = None;
+    let finish = |current: Option<(ColorScheme, [bool; 6])>| match current {
+        Some((scheme, seen)) if seen.iter().all(|&s| s) => Ok(Some(scheme)),
+        Some((scheme, _)) => Err(format!(
+            "scheme {} doesn't set all six colours",
+            scheme.name
+        )),
+        None => Ok(None),
+    };
+
+    for (line_nr, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let invalid = || format!("line {}: invalid {}", line_nr + 1, line);
+        let mut parts = line.split_whitespace();
+        let key = parts.next().ok_or_else(invalid)?;
+        if key == "scheme" {
+            schemes.extend(finish(current.take())?);
+            let name = line["scheme".len()..].trim();
+            if name.is_empty() {
+                return Err(invalid());
+            }
+            let mut scheme = ColorScheme::standard();
+            scheme.name = name.to_string();
+            current = Some((scheme, [false; 6]));
+            continue;
+        }
+
+        let (scheme, seen) = current
+            .as_mut()
+            .ok_or_else(|| format!("line {}: colour outside of a scheme", line_nr + 1))?;
+        let color = Color::ALL
+            .iter()
+            .position(|c| format!("{:?}", c) == key)
+            .ok_or_else(invalid)?;
+        scheme.colors[color] = parts.next().and_then(parse_hex).ok_or_else(invalid)?;
+        if let Some(letter) = parts.next() {
+            let mut chars = letter.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => scheme.letters[color] = c,
+                _ => return Err(invalid()),
+            }
+        }
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+        seen[color] = true;
+    }
+    schemes.extend(finish(current)?);
+    Ok(schemes)
+}
+
+pub fn load_schemes<P: AsRef<Path>>(path: P) -> Result<Vec<ColorScheme>, String> {
+    let path = path.as_ref();
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    parse_schemes(&text)
+}
@@ -0,0 +1,200 @@
+//! Everything the visualizer restores when it is opened again.
+//!
+//! The session is stored through eframe's persistence as a line based text:
+//!
+//! ```text
+//! # rubiks session v1
+//! cube WWWWWWWWWYYYYYYYYYGGGGGGGGGBBBBBBBBBRRRRRRRRROOOOOOOOO
+//! history R U R'
+//! redo U
+//! view 3d
+//! camera -0.6 0.5 10
+//! animation true 4
+//! solver ida 5 14
+//! colors false Standard
+//! bookmark <54-char state> <name>
+//! ```
+//!
+//! Unknown keys are ignored so that older sessions stay readable.
+use crate::rubiks::cube::RubiksCube;
+use crate::rubiks::moves::{algorithm_to_string, parse_algorithm, Move};
+use crate::ui::animation::SPEEDS;
+use crate::ui::app::View;
+use crate::ui::player::{SolverChoice, SolverSettings, MAX_DEPTHS, TABLE_DEPTHS};
+use crate::ui::view3d::Camera;
+use std::fmt;
+use std::str::FromStr;
+
+const HEADER: &str = "# rubiks session v1";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bookmark {
+    pub name: String,
+    pub cube: RubiksCube,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Session {
+    pub cube: RubiksCube,
+    pub history: Vec<Move>,
+    // Undone moves, the next one to redo last
+    pub redo: Vec<Move>,
+    pub bookmarks: Vec<Bookmark>,
+    pub view: View,
+    pub camera: Camera,
+    pub animate: bool,
+    pub speed: f32,
+    pub solver: SolverSettings,
+    // Name of the colour scheme and whether letters are shown on it
+    pub scheme: String,
+    pub letters: bool,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Session {
+            cube: RubiksCube::new(),
+            history: Vec::new(),
+            redo: Vec::new(),
+            bookmarks: Vec::new(),
+            view: View::Net,
+            camera: Camera::default(),
+            animate: true,
+            speed: 4.0,
+            solver: SolverSettings::default(),
+            scheme: "Standard".to_string(),
+            letters: false,
+        }
+    }
+}
+
+fn solver_key(solver: SolverChoice) -> &'static str {
+    match solver {
+        SolverChoice::Bfs => "bfs",
+        SolverChoice::Ida => "ida",
+        SolverChoice::AStar => "astar",
+    }
+}
+
+impl fmt::Display for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", HEADER)?;
+        writeln!(f, "cube {}", self.cube.to_string())?;
+        writeln!(f, "history {}", algorithm_to_string(&self.history))?;
+        writeln!(f, "redo {}", algorithm_to_string(&self.redo))?;
+        let view = match self.view {
+            View::Net => "net",
+            View::Perspective => "3d",
+        };
+        writeln!(f, "view {}", view)?;
+        writeln!(
+            f,
+            "camera {} {} {}",
+            self.camera.yaw, self.camera.pitch, self.camera.distance
+        )?;
+        writeln!(f, "animation {} {}", self.animate, self.speed)?;
+        writeln!(
+            f,
+            "solver {} {} {}",
+            solver_key(self.solver.solver),
+            self.solver.table_depth,
+            self.solver.max_depth
+        )?;
+        writeln!(f, "colors {} {}", self.letters, self.scheme)?;
+        for bookmark in &self.bookmarks {
+            writeln!(
+                f,
+                "bookmark {} {}",
+                bookmark.cube.to_string(),
+                bookmark.name
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_values<T: FromStr>(value: &str, count: usize) -> Option<Vec<T>> {
+    let values: Vec<T> = value
+        .split_whitespace()
+        .map(|v| v.parse().ok())
+        .collect::<Option<_>>()?;
+    (values.len() == count).then_some(values)
+}
+
+impl FromStr for Session {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines();
+        if lines.next() != Some(HEADER) {
+            return Err("not a rubiks session".to_string());
+        }
+
+        let mut session = Session::default();
+        for (line_nr, line) in lines.enumerate() {
+            let invalid = || format!("line {}: invalid {}", line_nr + 2, line);
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            match key {
+                "cube" => {
+                    session.cube = RubiksCube::from_state_string(value).map_err(|_| invalid())?
+                }
+                "history" => session.history = parse_algorithm(value)?,
+                "redo" => session.redo = parse_algorithm(value)?,
+                "view" => {
+                    session.view = match value {
+                        "net" => View::Net,
+                        "3d" => View::Perspective,
+                        _ => return Err(invalid()),
+                    }
+                }
+                "camera" => {
+                    let values = parse_values::<f32>(value, 3).ok_or_else(invalid)?;
+                    session.camera = Camera {
+                        yaw: values[0],
+                        pitch: values[1],
+                        distance: values[2],
+                    };
+                }
+                "animation" => {
+                    let (animate, speed) = value.split_once(' ').ok_or_else(invalid)?;
+                    session.animate = animate.parse().map_err(|_| invalid())?;
+                    session.speed = speed
+                        .parse()
+                        .ok()
+                        .filter(|speed| SPEEDS.contains(speed))
+                        .ok_or_else(invalid)?;
+                }
+                "solver" => {
+                    let (solver, depths) = value.split_once(' ').ok_or_else(invalid)?;
+                    let depths = parse_values::<usize>(depths, 2)
+                        .filter(|depths| {
+                            TABLE_DEPTHS.contains(&depths[0]) && MAX_DEPTHS.contains(&depths[1])
+                        })
+                        .ok_or_else(invalid)?;
+                    session.solver = SolverSettings {
+                        solver: SolverChoice::ALL
+                            .into_iter()
+                            .find(|&choice| solver_key(choice) == solver)
+                            .ok_or_else(invalid)?,
+                        table_depth: depths[0],
+                        max_depth: depths[1],
+                    };
+                }
+                "colors" => {
+                    let (letters, scheme) = value.split_once(' ').ok_or_else(invalid)?;
+                    session.letters = letters.parse().map_err(|_| invalid())?;
+                    session.scheme = scheme.to_string();
+                }
+                "bookmark" => {
+                    let (cube, name) = value.split_once(' ').unwrap_or((value, ""));
+                    session.bookmarks.push(Bookmark {
+                        name: name.to_string(),
+                        cube: RubiksCube::from_state_string(cube).map_err(|_| invalid())?,
+                    });
+                }
+                _ => {}
+            }
+        }
+        Ok(session)
+    }
+}
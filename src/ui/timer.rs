@@ -0,0 +1,208 @@
+//! Speedcubing timer with WCA inspection and session statistics.
+//!
+//! The spacebar drives the timer: releasing it starts the 15 second
+//! inspection, releasing it again starts the solve and pressing it stops the
+//! solve. Starting the solve more than 15 seconds into inspection costs two
+//! seconds, more than 17 seconds is a DNF (WCA regulation A3).
+use crate::rubiks::moves::{algorithm_to_string, Move};
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+pub const INSPECTION: Duration = Duration::from_secs(15);
+// Inspection after which the solve is a DNF instead of +2
+const INSPECTION_LIMIT: Duration = Duration::from_secs(17);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Penalty {
+    None,
+    PlusTwo,
+    Dnf,
+}
+
+impl Penalty {
+    pub fn name(self) -> &'static str {
+        match self {
+            Penalty::None => "none",
+            Penalty::PlusTwo => "+2",
+            Penalty::Dnf => "DNF",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solve {
+    pub scramble: Vec<Move>,
+    // Time on the clock, without the penalty
+    pub time: Duration,
+    pub penalty: Penalty,
+}
+
+impl Solve {
+    // Time that counts, None for a DNF
+    pub fn result(&self) -> Option<Duration> {
+        match self.penalty {
+            Penalty::None => Some(self.time),
+            Penalty::PlusTwo => Some(self.time + Duration::from_secs(2)),
+            Penalty::Dnf => None,
+        }
+    }
+}
+
+// Seconds with two decimals, or DNF
+pub fn format_result(result: Option<Duration>) -> String {
+    match result {
+        Some(time) => format!("{:.2}", time.as_secs_f64()),
+        None => "DNF".to_string(),
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TimerState {
+    Idle,
+    Inspecting { start: Instant },
+    Running { start: Instant, penalty: Penalty },
+    // Stopped, waiting for the spacebar to come up again
+    Stopped,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Timer {
+    pub state: TimerState,
+    // Skip inspection and start the solve right away
+    pub inspection: bool,
+}
+
+impl Default for Timer {
+    fn default() -> Self {
+        Timer {
+            state: TimerState::Idle,
+            inspection: true,
+        }
+    }
+}
+
+impl Timer {
+    pub fn space_released(&mut self, now: Instant) {
+        self.state = match self.state {
+            TimerState::Idle if self.inspection => TimerState::Inspecting { start: now },
+            TimerState::Idle => TimerState::Running {
+                start: now,
+                penalty: Penalty::None,
+            },
+            TimerState::Inspecting { start } => {
+                let inspection = now - start;
+                let penalty = if inspection > INSPECTION_LIMIT {
+                    Penalty::Dnf
+                } else if inspection > INSPECTION {
+                    Penalty::PlusTwo
+                } else {
+                    Penalty::None
+                };
+                TimerState::Running {
+                    start: now,
+                    penalty,
+                }
+            }
+            TimerState::Stopped => TimerState::Idle,
+            running => running,
+        };
+    }
+
+    // Stops a running solve and returns its time and penalty
+    pub fn space_pressed(&mut self, now: Instant) -> Option<(Duration, Penalty)> {
+        match self.state {
+            TimerState::Running { start, penalty } => {
+                self.state = TimerState::Stopped;
+                Some((now - start, penalty))
+            }
+            _ => None,
+        }
+    }
+
+    // Inspection time left, negative once it is over
+    pub fn inspection_left(&self, now: Instant) -> Option<f64> {
+        match self.state {
+            TimerState::Inspecting { start } => {
+                Some(INSPECTION.as_secs_f64() - (now - start).as_secs_f64())
+            }
+            _ => None,
+        }
+    }
+
+    pub fn elapsed(&self, now: Instant) -> Option<Duration> {
+        match self.state {
+            TimerState::Running { start, .. } => Some(now - start),
+            _ => None,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        matches!(
+            self.state,
+            TimerState::Inspecting { .. } | TimerState::Running { .. }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TimerSession {
+    pub solves: Vec<Solve>,
+}
+
+impl TimerSession {
+    pub fn best(&self) -> Option<Duration> {
+        self.solves.iter().filter_map(Solve::result).min()
+    }
+
+    // Mean of all solves that aren't DNF
+    pub fn mean(&self) -> Option<Duration> {
+        let results: Vec<Duration> = self.solves.iter().filter_map(Solve::result).collect();
+        if results.is_empty() {
+            return None;
+        }
+        Some(results.iter().sum::<Duration>() / results.len() as u32)
+    }
+
+    // WCA style average of the last `n` solves: the best and worst 5% (at
+    // least one each) are dropped and the rest is averaged. DNFs count as
+    // the worst results, so the average is a DNF if more than the dropped
+    // number of solves are DNFs. None if there are fewer than `n` solves,
+    // Some(None) for a DNF average.
+    pub fn average_of(&self, n: usize) -> Option<Option<Duration>> {
+        if n < 3 || self.solves.len() < n {
+            return None;
+        }
+        let trim = n.div_ceil(20);
+        let mut results: Vec<Option<Duration>> = self.solves[self.solves.len() - n..]
+            .iter()
+            .map(Solve::result)
+            .collect();
+        // None sorts before Some, so put DNFs last
+        results.sort_by_key(|result| (result.is_none(), *result));
+        let counted: Option<Vec<Duration>> = results[trim..n - trim].iter().cloned().collect();
+        Some(counted.map(|times| times.iter().sum::<Duration>() / times.len() as u32))
+    }
+
+    pub fn write_csv<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = fs::File::create(path)?;
+        writeln!(file, "solve,time,penalty,result,scramble")?;
+        for (i, solve) in self.solves.iter().enumerate() {
+            writeln!(
+                file,
+                "{},{:.3},{},{},{}",
+                i + 1,
+                solve.time.as_secs_f64(),
+                solve.penalty.name(),
+                format_result(solve.result()),
+                algorithm_to_string(&solve.scramble)
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,170 @@
+//! Drilling last layer cases.
+//!
+//! The trainer puts a random case from the selected ones on the cube. It is
+//! answered either by naming it or by performing its algorithm until the
+//! cube is solved, and the accuracy and answer time are kept per case.
+use crate::rubiks::cases::{is_solved_up_to_auf, Case, CaseSet};
+use crate::rubiks::cube::RubiksCube;
+use rand::Rng;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TrainerMode {
+    // Name the case shown
+    Recognize,
+    // Solve it with the move controls
+    Perform,
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct CaseStats {
+    pub attempts: u32,
+    pub correct: u32,
+    // Time spent on the correct attempts
+    pub time: Duration,
+}
+
+impl CaseStats {
+    pub fn accuracy(&self) -> Option<f64> {
+        (self.attempts > 0).then(|| self.correct as f64 / self.attempts as f64)
+    }
+
+    // Mean time of a correct answer
+    pub fn mean_time(&self) -> Option<Duration> {
+        (self.correct > 0).then(|| self.time / self.correct)
+    }
+}
+
+// The case being asked and since when
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Attempt {
+    pub case: usize,
+    pub start: Instant,
+}
+
+// How the last attempt went, with the time it took
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Answer {
+    pub case: usize,
+    pub correct: bool,
+    pub time: Duration,
+}
+
+pub struct Trainer {
+    cases: Vec<&'static Case>,
+    // Which cases are drilled, by index into `cases`
+    pub selected: Vec<bool>,
+    pub mode: TrainerMode,
+    stats: Vec<CaseStats>,
+    current: Option<Attempt>,
+    last: Option<Answer>,
+}
+
+impl Default for Trainer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Trainer {
+    // All OLL and PLL cases, with the PLLs selected
+    pub fn new() -> Self {
+        let cases: Vec<&'static Case> = CaseSet::ALL
+            .iter()
+            .flat_map(|set| set.cases().iter())
+            .collect();
+        Trainer {
+            selected: cases.iter().map(|case| case.set == CaseSet::Pll).collect(),
+            stats: vec![CaseStats::default(); cases.len()],
+            cases,
+            mode: TrainerMode::Recognize,
+            current: None,
+            last: None,
+        }
+    }
+
+    pub fn cases(&self) -> &[&'static Case] {
+        &self.cases
+    }
+
+    pub fn stats(&self, case: usize) -> CaseStats {
+        self.stats[case]
+    }
+
+    pub fn current(&self) -> Option<Attempt> {
+        self.current
+    }
+
+    pub fn last_answer(&self) -> Option<Answer> {
+        self.last
+    }
+
+    // Indices of the selected cases
+    pub fn selection(&self) -> Vec<usize> {
+        (0..self.cases.len())
+            .filter(|&i| self.selected[i])
+            .collect()
+    }
+
+    pub fn select_set(&mut self, set: CaseSet, selected: bool) {
+        for (case, s) in self.cases.iter().zip(&mut self.selected) {
+            if case.set == set {
+                *s = selected;
+            }
+        }
+    }
+
+    // Starts a random selected case seen from a random side, and returns
+    // the cube to show. None if no case is selected.
+    pub fn next_case<R: Rng>(&mut self, rng: &mut R, now: Instant) -> Option<RubiksCube> {
+        let selection = self.selection();
+        if selection.is_empty() {
+            return None;
+        }
+        let case = selection[rng.gen_range(0..selection.len())];
+        self.current = Some(Attempt { case, start: now });
+        Some(self.cases[case].setup(rng.gen_range(0..4), rng.gen_range(0..4)))
+    }
+
+    fn answer(&mut self, correct: bool, now: Instant) -> Option<Answer> {
+        let attempt = self.current.take()?;
+        let time = now - attempt.start;
+        let stats = &mut self.stats[attempt.case];
+        stats.attempts += 1;
+        if correct {
+            stats.correct += 1;
+            stats.time += time;
+        }
+        self.last = Some(Answer {
+            case: attempt.case,
+            correct,
+            time,
+        });
+        self.last
+    }
+
+    // Names the current case
+    pub fn guess(&mut self, case: usize, now: Instant) -> Option<Answer> {
+        let correct = self.current?.case == case;
+        self.answer(correct, now)
+    }
+
+    // Counts the current case as done once `cube` is solved
+    pub fn check(&mut self, cube: &RubiksCube, now: Instant) -> Option<Answer> {
+        self.current?;
+        if is_solved_up_to_auf(cube) {
+            self.answer(true, now)
+        } else {
+            None
+        }
+    }
+
+    pub fn give_up(&mut self, now: Instant) -> Option<Answer> {
+        self.answer(false, now)
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = vec![CaseStats::default(); self.cases.len()];
+        self.last = None;
+    }
+}
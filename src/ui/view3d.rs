@@ -0,0 +1,381 @@
+//! Perspective view of the cube that can be orbited with the mouse.
+//!
+//! The cube spans -1.5..1.5 on every axis with x pointing right, y up and
+//! z towards the viewer, so every sticker is a unit square on the surface.
+use crate::rubiks::cube::RubiksCube;
+use crate::rubiks::moves::{Face, Move, Turn};
+use crate::ui::scheme::ColorScheme;
+use eframe::egui::{Align2, Color32, FontId, Painter, Pos2, Rect, Shape, Stroke, Vec2};
+use std::f32::consts::FRAC_PI_2;
+
+pub type Vec3 = [f32; 3];
+
+// Outward normal, column direction and row direction of every face in
+// RubiksCube::faces order, matching the sticker numbering of the moves
+const FACE_AXES: [(Vec3, Vec3, Vec3); 6] = [
+    ([0.0, 1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]), // Up
+    ([0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, -1.0]), // Down
+    ([0.0, 0.0, 1.0], [1.0, 0.0, 0.0], [0.0, -1.0, 0.0]), // Front
+    ([0.0, 0.0, -1.0], [-1.0, 0.0, 0.0], [0.0, -1.0, 0.0]), // Back
+    ([1.0, 0.0, 0.0], [0.0, 0.0, -1.0], [0.0, -1.0, 0.0]), // Right
+    ([-1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, -1.0, 0.0]), // Left
+];
+
+fn add(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn scale(a: Vec3, s: f32) -> Vec3 {
+    [a[0] * s, a[1] * s, a[2] * s]
+}
+
+fn dot(a: Vec3, b: Vec3) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+pub fn face_normal(face: usize) -> Vec3 {
+    FACE_AXES[face].0
+}
+
+// Point on a face given its coordinates along the column and row direction,
+// both running from -1.5 to 1.5
+fn face_point(face: usize, u: f32, v: f32) -> Vec3 {
+    let (normal, right, down) = FACE_AXES[face];
+    add(scale(normal, 1.5), add(scale(right, u), scale(down, v)))
+}
+
+pub fn sticker_center(face: usize, index: usize) -> Vec3 {
+    face_point(face, (index % 3) as f32 - 1.0, (index / 3) as f32 - 1.0)
+}
+
+// Corners of a sticker, shrunk towards its centre by `inset`
+pub fn sticker_corners(face: usize, index: usize, inset: f32) -> [Vec3; 4] {
+    let u = (index % 3) as f32 - 1.0;
+    let v = (index / 3) as f32 - 1.0;
+    let h = 0.5 - inset;
+    [
+        face_point(face, u - h, v - h),
+        face_point(face, u + h, v - h),
+        face_point(face, u + h, v + h),
+        face_point(face, u - h, v + h),
+    ]
+}
+
+// The sticker with the given centre and normal, if there is one
+pub fn sticker_at(center: Vec3, normal: Vec3) -> Option<(usize, usize)> {
+    (0..6)
+        .flat_map(|face| (0..9).map(move |index| (face, index)))
+        .find(|&(face, index)| {
+            dot(face_normal(face), normal) > 0.5
+                && sticker_center(face, index)
+                    .iter()
+                    .zip(&center)
+                    .all(|(a, b)| (a - b).abs() < 1e-3)
+        })
+}
+
+// Rotation by `angle` (counterclockwise when looking from the tip of `axis`)
+// around a coordinate axis through the centre of the cube
+pub fn rotate(p: Vec3, axis: Vec3, angle: f32) -> Vec3 {
+    let (s, c) = angle.sin_cos();
+    let [x, y, z] = p;
+    if axis[0].abs() > 0.5 {
+        let s = s * axis[0].signum();
+        [x, y * c - z * s, y * s + z * c]
+    } else if axis[1].abs() > 0.5 {
+        let s = s * axis[1].signum();
+        [x * c + z * s, y, -x * s + z * c]
+    } else {
+        let s = s * axis[2].signum();
+        [x * c - y * s, x * s + y * c, z]
+    }
+}
+
+// Axis and angle of the layer rotation performed by a move
+pub fn turn_rotation(m: Move) -> (Vec3, f32) {
+    let angle = match m.turn {
+        Turn::Clockwise => -FRAC_PI_2,
+        Turn::Double => -2.0 * FRAC_PI_2,
+        Turn::CounterClockwise => FRAC_PI_2,
+    };
+    (face_normal(m.face.index()), angle)
+}
+
+// Whether a point belongs to the layer turned by a move on `face`
+pub fn in_layer(face: Face, p: Vec3) -> bool {
+    dot(face_normal(face.index()), p) > 0.9
+}
+
+// Orbiting camera looking at the centre of the cube
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Camera {
+    // Rotation of the cube around the vertical axis
+    pub yaw: f32,
+    // Tilt towards the viewer, positive shows the top face
+    pub pitch: f32,
+    pub distance: f32,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Camera {
+            yaw: -0.6,
+            pitch: 0.5,
+            distance: 10.0,
+        }
+    }
+}
+
+impl Camera {
+    // Orbit by a mouse drag in points
+    pub fn orbit(&mut self, delta: Vec2) {
+        self.yaw += delta.x * 0.01;
+        self.pitch = (self.pitch + delta.y * 0.01).clamp(-1.5, 1.5);
+    }
+
+    fn view_space(&self, p: Vec3) -> Vec3 {
+        rotate(
+            rotate(p, [0.0, 1.0, 0.0], self.yaw),
+            [1.0, 0.0, 0.0],
+            self.pitch,
+        )
+    }
+
+    // Screen position and distance from the eye of a point on the cube
+    pub fn project(&self, p: Vec3, rect: Rect) -> (Pos2, f32) {
+        let [x, y, z] = self.view_space(p);
+        let depth = self.distance - z;
+        let focal = rect.width().min(rect.height()) * 1.2;
+        (rect.center() + Vec2::new(x, -y) * focal / depth, depth)
+    }
+
+    // Whether a surface through `point` with outward `normal` faces the eye
+    pub fn faces_eye(&self, point: Vec3, normal: Vec3) -> bool {
+        let normal = self.view_space(normal);
+        let point = self.view_space(point);
+        dot(normal, [-point[0], -point[1], self.distance - point[2]]) > 0.0
+    }
+
+    // Whether the outside of a face points towards the eye
+    pub fn is_visible(&self, face: usize) -> bool {
+        let normal = face_normal(face);
+        self.faces_eye(scale(normal, 1.5), normal)
+    }
+}
+
+// A projected sticker
+struct Quad {
+    face: usize,
+    index: usize,
+    points: Vec<Pos2>,
+    depth: f32,
+}
+
+fn project_quad(
+    camera: &Camera,
+    rect: Rect,
+    face: usize,
+    index: usize,
+    corners: [Vec3; 4],
+) -> Quad {
+    let projected: Vec<(Pos2, f32)> = corners.iter().map(|&p| camera.project(p, rect)).collect();
+    Quad {
+        face,
+        index,
+        points: projected.iter().map(|&(p, _)| p).collect(),
+        depth: projected.iter().map(|&(_, d)| d).sum::<f32>() / 4.0,
+    }
+}
+
+fn contains(points: &[Pos2], pos: Pos2) -> bool {
+    let crosses: Vec<f32> = (0..points.len())
+        .map(|i| {
+            let a = points[i];
+            let b = points[(i + 1) % points.len()];
+            (b - a).x * (pos - a).y - (b - a).y * (pos - a).x
+        })
+        .collect();
+    crosses.iter().all(|&c| c >= 0.0) || crosses.iter().all(|&c| c <= 0.0)
+}
+
+// Stickers of the visible faces
+fn visible_stickers(camera: &Camera, rect: Rect) -> Vec<Quad> {
+    (0..6)
+        .filter(|&face| camera.is_visible(face))
+        .flat_map(|face| (0..9).map(move |index| (face, index)))
+        .map(|(face, index)| {
+            project_quad(camera, rect, face, index, sticker_corners(face, index, 0.0))
+        })
+        .collect()
+}
+
+// A flat part of the surface of one of the two bodies the cube splits into
+// while a layer turns
+struct Polygon {
+    corners: [Vec3; 4],
+    normal: Vec3,
+    color: Color32,
+    // Letter written on a sticker, and its colour
+    label: Option<(char, Color32)>,
+}
+
+impl Polygon {
+    fn rotated(&self, axis: Vec3, angle: f32) -> Polygon {
+        Polygon {
+            corners: self.corners.map(|p| rotate(p, axis, angle)),
+            normal: rotate(self.normal, axis, angle),
+            color: self.color,
+            label: self.label,
+        }
+    }
+}
+
+// The bodies are convex, so hiding the polygons turned away from the eye
+// is enough to draw each of them correctly
+fn draw_body(painter: &Painter, rect: Rect, camera: &Camera, body: &[Polygon]) {
+    for polygon in body {
+        let center = scale(
+            polygon.corners.iter().fold([0.0; 3], |a, &b| add(a, b)),
+            0.25,
+        );
+        if camera.faces_eye(center, polygon.normal) {
+            let points: Vec<Pos2> = polygon
+                .corners
+                .iter()
+                .map(|&p| camera.project(p, rect).0)
+                .collect();
+            // Scale the letter with the sticker's size on screen
+            let size = (points[0].distance(points[2]) + points[1].distance(points[3])) * 0.2;
+            painter.add(Shape::convex_polygon(points, polygon.color, Stroke::NONE));
+            if let Some((letter, color)) = polygon.label {
+                painter.text(
+                    camera.project(center, rect).0,
+                    Align2::CENTER_CENTER,
+                    letter,
+                    FontId::proportional(size),
+                    color,
+                );
+            }
+        }
+    }
+}
+
+// Draws `cube`, optionally with the layer of a move turned part of the way
+// (`progress` between 0 and 1 of the move)
+pub fn draw_cube_3d(
+    cube: &RubiksCube,
+    scheme: &ColorScheme,
+    painter: &Painter,
+    rect: Rect,
+    camera: &Camera,
+    highlight: Option<Face>,
+    turn: Option<(Move, f32)>,
+) {
+    let rotation = turn.map(|(m, progress)| {
+        let (axis, angle) = turn_rotation(m);
+        (m.face, axis, angle * progress)
+    });
+    let mut fixed = Vec::new();
+    let mut turning = Vec::new();
+    for face in 0..6 {
+        for index in 0..9 {
+            let color = cube.faces[face][index];
+            let sticker = [
+                Polygon {
+                    corners: sticker_corners(face, index, 0.0),
+                    normal: face_normal(face),
+                    color: Color32::BLACK,
+                    label: None,
+                },
+                Polygon {
+                    corners: sticker_corners(face, index, 0.06),
+                    normal: face_normal(face),
+                    color: scheme.color(color),
+                    label: scheme
+                        .show_letters
+                        .then(|| (scheme.letter(color), scheme.text_color(color))),
+                },
+            ];
+            match rotation {
+                Some((layer, axis, angle)) if in_layer(layer, sticker_center(face, index)) => {
+                    turning.extend(sticker.iter().map(|p| p.rotated(axis, angle)))
+                }
+                _ => fixed.extend(sticker),
+            }
+        }
+    }
+
+    match rotation {
+        Some((layer, axis, angle)) => {
+            // Close the cut between the turning layer and the rest in black
+            let (normal, right, down) = FACE_AXES[layer.index()];
+            let cut = [(-1.5, -1.5), (1.5, -1.5), (1.5, 1.5), (-1.5, 1.5)]
+                .map(|(u, v)| add(scale(normal, 0.5), add(scale(right, u), scale(down, v))));
+            fixed.push(Polygon {
+                corners: cut,
+                normal,
+                color: Color32::BLACK,
+                label: None,
+            });
+            turning.push(
+                Polygon {
+                    corners: cut,
+                    normal: scale(normal, -1.0),
+                    color: Color32::BLACK,
+                    label: None,
+                }
+                .rotated(axis, angle),
+            );
+            // The body on the far side of the cut is drawn first
+            if camera.faces_eye(scale(normal, 0.5), normal) {
+                draw_body(painter, rect, camera, &fixed);
+                draw_body(painter, rect, camera, &turning);
+            } else {
+                draw_body(painter, rect, camera, &turning);
+                draw_body(painter, rect, camera, &fixed);
+            }
+        }
+        None => draw_body(painter, rect, camera, &fixed),
+    }
+
+    if let Some(face) = highlight.filter(|face| camera.is_visible(face.index())) {
+        let outline: Vec<Pos2> = [(-1.5, -1.5), (1.5, -1.5), (1.5, 1.5), (-1.5, 1.5)]
+            .iter()
+            .map(|&(u, v)| camera.project(face_point(face.index(), u, v), rect).0)
+            .collect();
+        painter.add(Shape::convex_polygon(
+            outline,
+            Color32::from_white_alpha(60),
+            Stroke::new(3.0, Color32::from_gray(40)),
+        ));
+    }
+}
+
+// Face and sticker index under a screen position
+pub fn sticker_under(camera: &Camera, rect: Rect, pos: Pos2) -> Option<(Face, usize)> {
+    visible_stickers(camera, rect)
+        .into_iter()
+        .filter(|quad| contains(&quad.points, pos))
+        .min_by(|a, b| a.depth.total_cmp(&b.depth))
+        .map(|quad| (Face::ALL[quad.face], quad.index))
+}
+
+// The turn of `face` for a drag from `start` to `end`: dragging around the
+// centre of the face clockwise on screen turns it clockwise. Returns None
+// for drags too short to tell the direction.
+pub fn drag_turn(camera: &Camera, rect: Rect, face: Face, start: Pos2, end: Pos2) -> Option<Move> {
+    let (center, _) = camera.project(scale(face_normal(face.index()), 1.5), rect);
+    let radius = start - center;
+    let drag = end - start;
+    if drag.length() < 10.0 || radius.length() < 1.0 {
+        return None;
+    }
+    // Screen y points down, so a positive cross product is clockwise
+    let cross = radius.x * drag.y - radius.y * drag.x;
+    let turn = if cross > 0.0 {
+        Turn::Clockwise
+    } else {
+        Turn::CounterClockwise
+    };
+    Some(Move::new(face, turn))
+}
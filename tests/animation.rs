@@ -0,0 +1,54 @@
+use rubiks::rubiks::cube::RubiksCube;
+use rubiks::rubiks::moves::parse_algorithm;
+use rubiks::ui::animation::Animation;
+
+#[test]
+fn queued_turns_play_in_order() {
+    let moves = parse_algorithm("R U2 F'").unwrap();
+    let mut animation = Animation::new(RubiksCube::new());
+    animation.speed = 1.0;
+    for &m in &moves {
+        animation.push(m);
+    }
+    assert_eq!(animation.current(), Some((moves[0], 0.0)));
+
+    // Three queued turns are played three times as fast
+    assert!(animation.update(0.1));
+    let (m, progress) = animation.current().unwrap();
+    assert_eq!(m, moves[0]);
+    assert!((progress - 0.3).abs() < 1e-5);
+
+    let mut expected = RubiksCube::new();
+    expected.apply_move(moves[0]);
+    assert!(animation.update(0.3));
+    assert_eq!(animation.current().map(|(m, _)| m), Some(moves[1]));
+    assert_eq!(animation.shown(), &expected);
+
+    while animation.update(0.05) {}
+    expected.apply_moves(&moves[1..]);
+    assert_eq!(animation.shown(), &expected);
+    assert_eq!(animation.pending(), 0);
+}
+
+#[test]
+fn disabled_or_skipped_turns_show_at_once() {
+    let moves = parse_algorithm("L D'").unwrap();
+    let mut cube = RubiksCube::new();
+    cube.apply_moves(&moves);
+
+    let mut animation = Animation::new(RubiksCube::new());
+    animation.enabled = false;
+    for &m in &moves {
+        animation.push(m);
+    }
+    assert_eq!(animation.current(), None);
+    assert_eq!(animation.shown(), &cube);
+
+    let mut animation = Animation::new(RubiksCube::new());
+    for &m in &moves {
+        animation.push(m);
+    }
+    animation.jump_to(&cube);
+    assert!(!animation.update(0.1));
+    assert_eq!(animation.shown(), &cube);
+}
@@ -0,0 +1,56 @@
+use rubiks::ml::neural_network::NeuralNetwork;
+use rubiks::rubiks::astar::AStarConfig;
+use rubiks::rubiks::cube::RubiksCube;
+use rubiks::rubiks::table::DistanceTable;
+
+#[test]
+fn astar_with_admissible_heuristic_is_optimal() {
+    let table = DistanceTable::new(2);
+    let cube = RubiksCube::from_scramble("U R' F D");
+    let result = cube
+        .solve_weighted_astar(&table, &AStarConfig::default())
+        .unwrap();
+    assert_eq!(result.solution.len(), 4);
+
+    let mut solved = cube.clone();
+    solved.apply_scramble(&result.solution.join(" "));
+    assert!(solved.is_solved());
+}
+
+#[test]
+fn astar_accepts_any_estimator() {
+    let cube = RubiksCube::from_scramble("L B'");
+    let config = AStarConfig {
+        weight: 2.0,
+        batch_size: 4,
+        max_nodes: 10_000,
+        ..AStarConfig::default()
+    };
+    let zero = |_: &RubiksCube| 0.0;
+    assert_eq!(
+        cube.solve_weighted_astar(&zero, &config)
+            .unwrap()
+            .solution
+            .len(),
+        2
+    );
+
+    let network = NeuralNetwork::with_seed(8, 1);
+    let result = cube.solve_weighted_astar(&network, &config).unwrap();
+    let mut solved = cube.clone();
+    solved.apply_scramble(&result.solution.join(" "));
+    assert!(solved.is_solved());
+}
+
+#[test]
+fn astar_respects_node_limit() {
+    let cube = RubiksCube::from_scramble("R U F L D B R U");
+    let config = AStarConfig {
+        weight: 1.0,
+        batch_size: 10,
+        max_nodes: 50,
+        ..AStarConfig::default()
+    };
+    let zero = |_: &RubiksCube| 0.0;
+    assert!(cube.solve_weighted_astar(&zero, &config).is_none());
+}
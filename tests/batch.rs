@@ -0,0 +1,184 @@
+use rubiks::ml::neural_network::NeuralNetwork;
+use rubiks::rubiks::astar::AStarConfig;
+use rubiks::rubiks::batch::{
+    parse_scrambles, read_results, solve_batch, BatchResult, BatchSummary, Solver, SolverConfig,
+};
+use rubiks::rubiks::cube::RubiksCube;
+use rubiks::rubiks::mcts::MctsConfig;
+use rubiks::rubiks::metric::Metric;
+use rubiks::rubiks::table::DistanceTable;
+use std::fs;
+
+// Times are stored in the CSV to the microsecond
+fn without_time(results: &[BatchResult]) -> Vec<(usize, String, Option<Vec<String>>, usize)> {
+    results
+        .iter()
+        .map(|r| {
+            (
+                r.index,
+                r.scramble.clone(),
+                r.solution.clone(),
+                r.nodes_expanded,
+            )
+        })
+        .collect()
+}
+
+fn ida(table: &DistanceTable) -> SolverConfig<'_, NeuralNetwork> {
+    SolverConfig {
+        solver: Solver::Ida,
+        table,
+        model: None,
+        kociemba: None,
+        max_depth: 6,
+        max_length: 30,
+        astar: AStarConfig::default(),
+        mcts: MctsConfig::default(),
+    }
+}
+
+#[test]
+fn scramble_files() {
+    let lines = "# warm-up\nR U\n\n  F2 L'  \n";
+    assert_eq!(parse_scrambles(lines).unwrap(), ["R U", "F2 L'"]);
+    let json = r#"[ "R U", "F2  L'" ]"#;
+    assert_eq!(parse_scrambles(json).unwrap(), ["R U", "F2 L'"]);
+    assert!(parse_scrambles("[]").unwrap().is_empty());
+    assert!(parse_scrambles("R U\nR, U").is_err());
+    assert!(parse_scrambles(r#"["R U", 3]"#).is_err());
+    assert!(parse_scrambles(r#"["R U""#).is_err());
+}
+
+#[test]
+fn solves_in_parallel_and_summarises() {
+    // The solvers only make quarter turns, so U2 R2 F' takes five moves
+    let scrambles = parse_scrambles("R U\nF\nL D' B\nU2 R2 F'").unwrap();
+    let table = DistanceTable::new(2);
+    let path = std::env::temp_dir()
+        .join("rubiks_batch")
+        .join("results.csv");
+    let _ = fs::remove_file(&path);
+
+    let results = solve_batch(&scrambles, &ida(&table), &path).unwrap();
+    assert_eq!(results.len(), 4);
+    for (index, result) in results.iter().enumerate() {
+        assert_eq!(result.index, index);
+        let mut cube = RubiksCube::from_scramble(&result.scramble);
+        cube.apply_scramble(&result.solution.as_ref().unwrap().join(" "));
+        assert!(cube.is_solved());
+    }
+    assert_eq!(
+        without_time(&read_results(&path).unwrap()),
+        without_time(&results)
+    );
+
+    let summary = BatchSummary::new(&results);
+    assert_eq!((summary.count, summary.solved), (4, 4));
+    assert_eq!(
+        summary.lengths.into_iter().collect::<Vec<_>>(),
+        [(1, 1), (2, 1), (3, 1), (5, 1)]
+    );
+    assert_eq!(summary.mean_length, 2.75);
+    assert!(BatchSummary::new(&results)
+        .to_json()
+        .contains("\"lengths\":{\"1\":1,\"2\":1,\"3\":1,\"5\":1}"));
+}
+
+#[test]
+fn resumes_from_partial_output() {
+    let scrambles = parse_scrambles("R U\nF\nL D' B").unwrap();
+    let table = DistanceTable::new(2);
+    let dir = std::env::temp_dir().join("rubiks_batch");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("partial.csv");
+    // An interrupted run: one result, one scramble the solver gave up on,
+    // then a line cut off while writing. The made-up node counts show which
+    // results are computed again.
+    fs::write(
+        &path,
+        "index,scramble,solved,solver,metric,length,nodes_expanded,time_ms,solution\n\
+         1,F,true,ida,qtm,1,777,0.010,F'\n\
+         2,L D' B,false,ida,qtm,,888,0.010,\n\
+         0,R U,tr",
+    )
+    .unwrap();
+
+    let results = solve_batch(&scrambles, &ida(&table), &path).unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[1].nodes_expanded, 777);
+    assert_eq!(results[0].solution.as_ref().unwrap().join(" "), "U' R'");
+    assert!(results[2].solution.is_some());
+    assert_ne!(results[2].nodes_expanded, 888);
+    assert_eq!(
+        without_time(&read_results(&path).unwrap()),
+        without_time(&results)
+    );
+    // Rewrites go through a temporary file that is renamed into place
+    assert!(!dir.join("partial.csv.tmp").exists());
+
+    // Results of other scrambles aren't mixed in
+    let other = parse_scrambles("D\nF").unwrap();
+    assert!(solve_batch(&other, &ida(&table), &path).is_err());
+    // Nor results in another metric
+    let htm = DistanceTable::with_metric(2, Metric::Htm);
+    assert!(solve_batch(&scrambles, &ida(&htm), &path).is_err());
+    // Nor those of another solver
+    let bfs = SolverConfig {
+        solver: Solver::Bfs,
+        ..ida(&table)
+    };
+    assert!(solve_batch(&scrambles, &bfs, &path).is_err());
+}
+
+#[test]
+fn output_may_end_in_tmp() {
+    // The temporary file is named after the output, not a replaced extension
+    let scrambles = parse_scrambles(
+        "R U
+F",
+    )
+    .unwrap();
+    let table = DistanceTable::new(2);
+    let dir = std::env::temp_dir().join("rubiks_batch");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("results.tmp");
+    let _ = fs::remove_file(&path);
+    let results = solve_batch(&scrambles, &ida(&table), &path).unwrap();
+    assert_eq!(
+        without_time(&read_results(&path).unwrap()),
+        without_time(&results)
+    );
+    assert!(!dir.join("results.tmp.tmp").exists());
+}
+
+#[test]
+fn lengths_are_in_the_metric_of_the_solver() {
+    // R L' is one slice turn, R2 U two face turns but three quarter turns
+    let scrambles = parse_scrambles("R L'\nR2 U").unwrap();
+    let dir = std::env::temp_dir().join("rubiks_batch");
+    fs::create_dir_all(&dir).unwrap();
+    for (metric, lengths) in [(Metric::Stm, [1, 2]), (Metric::Htm, [2, 2])] {
+        let table = DistanceTable::with_metric(2, metric);
+        let path = dir.join(format!("{}.csv", metric));
+        let _ = fs::remove_file(&path);
+        let results = solve_batch(&scrambles, &ida(&table), &path).unwrap();
+        assert_eq!(results[0].solution.as_ref().unwrap().len(), 2);
+        for (result, length) in results.iter().zip(lengths) {
+            assert_eq!(result.metric, metric);
+            assert_eq!(result.length(), Some(length));
+        }
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(text.contains(&format!("0,R L',true,ida,{},{},", metric, lengths[0])));
+        let read = read_results(&path).unwrap();
+        assert_eq!(without_time(&read), without_time(&results));
+        assert!(read.iter().all(|r| r.metric == metric));
+
+        let summary = BatchSummary::new(&results);
+        let expected: Vec<(usize, usize)> = if lengths[0] == lengths[1] {
+            vec![(2, 2)]
+        } else {
+            vec![(1, 1), (2, 1)]
+        };
+        assert_eq!(summary.lengths.into_iter().collect::<Vec<_>>(), expected);
+    }
+}
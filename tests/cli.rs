@@ -0,0 +1,138 @@
+use std::process::{Command, Output};
+
+fn rubiks(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_rubiks"))
+        .args(args)
+        .output()
+        .expect("the rubiks binary runs")
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn solve_and_verify() {
+    let output = rubiks(&["solve", "R U R'", "--format", "json"]);
+    assert_eq!(output.status.code(), Some(0));
+    let json = stdout(&output);
+    assert!(json.starts_with("{\"scramble\":\"R U R'\",\"solver\":\"ida\",\"solved\":true,"));
+    assert!(json.contains("\"solution\":\"R U' R'\",\"length\":3,\"metric\":\"qtm\","));
+
+    let output = rubiks(&["solve", "R2 U", "--metric", "htm", "--format", "json"]);
+    assert_eq!(output.status.code(), Some(0));
+    let json = stdout(&output);
+    assert!(json.contains("\"solution\":\"U' R2\",\"length\":2,\"metric\":\"htm\","));
+    assert!(json.contains("\"lengths\":{\"qtm\":3,\"htm\":2,\"stm\":2,\"atm\":2}"));
+
+    let output = rubiks(&["solve", "F B'", "--solver", "bfs"]);
+    assert_eq!(output.status.code(), Some(0));
+    let solution = stdout(&output);
+    assert_eq!(
+        rubiks(&["verify", "F B'", solution.trim()]).status.code(),
+        Some(0)
+    );
+
+    let output = rubiks(&["verify", "R U", "U' R", "--format", "json"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stdout(&output).trim(),
+        "{\"solved\":false,\"length\":2,\"lengths\":{\"qtm\":2,\"htm\":2,\"stm\":2,\"atm\":2}}"
+    );
+
+    // Too deep for the depth limit
+    let output = rubiks(&["solve", "R U F L", "--max-depth", "3"]);
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn kociemba_solves_deep_scrambles() {
+    let scramble = "D2 L' B2 R U2 F' L2 D B' U R2 F L' D2 B U' R F2 L U'";
+    let output = rubiks(&[
+        "solve", scramble, "--solver", "kociemba", "--metric", "htm", "--format", "json",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+    let json = stdout(&output);
+    assert!(json.contains("\"solver\":\"kociemba\",\"solved\":true,"));
+    let solution = json
+        .split("\"solution\":\"")
+        .nth(1)
+        .and_then(|rest| rest.split('"').next())
+        .unwrap();
+    let length = solution.split_whitespace().count();
+    assert!(length <= 30);
+    assert!(json.contains(&format!("\"length\":{},\"metric\":\"htm\",", length)));
+    assert_eq!(
+        rubiks(&["verify", scramble, solution]).status.code(),
+        Some(0)
+    );
+
+    // Nothing that short
+    let output = rubiks(&[
+        "solve",
+        scramble,
+        "--solver",
+        "kociemba",
+        "--max-length",
+        "8",
+    ]);
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn invalid_input_exits_with_2() {
+    assert_eq!(rubiks(&["solve", "R X"]).status.code(), Some(2));
+    assert_eq!(rubiks(&["verify", "R", "Q"]).status.code(), Some(2));
+    assert_eq!(
+        rubiks(&["solve", "R", "--solver", "thistlethwaite"])
+            .status
+            .code(),
+        Some(2)
+    );
+    assert_eq!(
+        rubiks(&["solve", "R", "--solver", "mcts"]).status.code(),
+        Some(2)
+    );
+    assert_eq!(
+        rubiks(&["solve", "R", "--metric", "otm"]).status.code(),
+        Some(2)
+    );
+}
+
+#[test]
+fn seeded_scrambles_repeat() {
+    let args = ["scramble", "--count", "3", "--seed", "42", "--length", "15"];
+    let first = stdout(&rubiks(&args));
+    assert_eq!(first, stdout(&rubiks(&args)));
+    let lines: Vec<&str> = first.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines
+        .iter()
+        .all(|line| line.split_whitespace().count() == 15));
+
+    let json = stdout(&rubiks(&[
+        "scramble", "--count", "2", "--seed", "42", "--format", "json",
+    ]));
+    assert!(json.starts_with("[\"") && json.trim_end().ends_with("\"]"));
+}
+
+#[test]
+fn render_writes_the_file() {
+    let dir = std::env::temp_dir().join("rubiks_cli");
+    let path = dir.join("t_perm.svg");
+    let output = rubiks(&[
+        "render",
+        "R U R' U' R' F R2 U' R' U' R U R' F'",
+        "--view",
+        "last-layer",
+        "--arrows",
+        "--letters",
+        "-o",
+        path.to_str().unwrap(),
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(std::fs::read_to_string(&path).unwrap().starts_with("<svg"));
+
+    let output = rubiks(&["render", "--scheme", "Pastel", "-o", path.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(2));
+}
@@ -0,0 +1,9 @@
+// Fixtures shared by the integration tests
+use rubiks::rubiks::table::DistanceTable;
+use std::sync::OnceLock;
+
+// Quarter turn distances up to 5, built once per test binary
+pub fn table() -> &'static DistanceTable {
+    static TABLE: OnceLock<DistanceTable> = OnceLock::new();
+    TABLE.get_or_init(|| DistanceTable::new(5))
+}
@@ -0,0 +1,85 @@
+use rubiks::rubiks::color::Color;
+use rubiks::rubiks::cube::RubiksCube;
+use rubiks::rubiks::cubie::{CubieCube, StateError, CORNER_FACELETS, EDGE_FACELETS};
+use rubiks::ui::editor::StateEditor;
+
+#[test]
+fn scrambled_states_are_valid_and_round_trip() {
+    assert_eq!(RubiksCube::new().validate(), Ok(CubieCube::new()));
+    for scramble in ["R", "U F'", "R U R' U' F2 D L' B", "L2 D' B R2 U' F L"] {
+        let cube = RubiksCube::from_scramble(scramble);
+        let cubie = cube.validate().unwrap();
+        assert_eq!(cubie.to_facelets(), cube, "{}", scramble);
+    }
+}
+
+#[test]
+fn twisted_corner_and_flipped_edge_are_reported() {
+    let mut cube = RubiksCube::from_scramble("R U");
+    let [a, b, c] = CORNER_FACELETS[0];
+    let colors = [a, b, c].map(|(face, index)| cube.faces[face][index]);
+    cube.faces[a.0][a.1] = colors[2];
+    cube.faces[b.0][b.1] = colors[0];
+    cube.faces[c.0][c.1] = colors[1];
+    assert_eq!(cube.validate(), Err(vec![StateError::CornerTwist]));
+
+    let mut cube = RubiksCube::new();
+    let [(f0, i0), (f1, i1)] = EDGE_FACELETS[5];
+    let color = cube.faces[f0][i0];
+    cube.faces[f0][i0] = cube.faces[f1][i1];
+    cube.faces[f1][i1] = color;
+    assert_eq!(cube.validate(), Err(vec![StateError::EdgeFlip]));
+}
+
+#[test]
+fn swapped_pieces_break_parity() {
+    // Swap the UF and UR edges
+    let mut cube = RubiksCube::new();
+    for ((f0, i0), (f1, i1)) in EDGE_FACELETS[0].into_iter().zip(EDGE_FACELETS[1]) {
+        let color = cube.faces[f0][i0];
+        cube.faces[f0][i0] = cube.faces[f1][i1];
+        cube.faces[f1][i1] = color;
+    }
+    assert_eq!(cube.validate(), Err(vec![StateError::Parity]));
+}
+
+#[test]
+fn wrong_stickers_are_reported() {
+    let mut cube = RubiksCube::new();
+    // UF now shows white twice
+    cube.faces[2][1] = Color::W;
+    let errors = cube.validate().unwrap_err();
+    assert!(errors.contains(&StateError::ColorCount {
+        color: Color::W,
+        count: 10
+    }));
+    assert!(errors.contains(&StateError::ColorCount {
+        color: Color::G,
+        count: 8
+    }));
+    assert!(errors.contains(&StateError::ImpossibleEdge { position: 1 }));
+
+    let mut cube = RubiksCube::new();
+    cube.faces[0][4] = Color::Y;
+    assert!(cube
+        .validate()
+        .unwrap_err()
+        .contains(&StateError::DuplicateCenters));
+}
+
+#[test]
+fn editor_keeps_centres_and_counts_colours() {
+    let mut editor = StateEditor::new(RubiksCube::new());
+    editor.brush = Color::R;
+    assert!(!editor.paint(0, 4));
+    assert!(editor.paint(0, 0));
+    assert!(!editor.paint(0, 0));
+    assert_eq!(editor.counts(), [8, 9, 9, 9, 10, 9]);
+    assert!(editor.validate().is_err());
+
+    // W comes after O when cycling
+    editor.brush = Color::O;
+    editor.paint(0, 0);
+    assert!(editor.cycle(0, 0));
+    assert_eq!(editor.validate(), Ok(Default::default()));
+}
@@ -0,0 +1,62 @@
+use rubiks::ml::dataset::{
+    generate, read_dataset, write_dataset, DepthDistribution, GeneratorConfig, Label,
+};
+use rubiks::rubiks::cube::RubiksCube;
+use rubiks::rubiks::table::DistanceTable;
+
+fn config(label: Label, seed: u64) -> GeneratorConfig {
+    GeneratorConfig {
+        size: 30,
+        depths: "1-4".parse().unwrap(),
+        label,
+        seed,
+        table_depth: 2,
+    }
+}
+
+#[test]
+fn exact_labels_match_bfs() {
+    for sample in generate(&config(Label::Exact, 7)).unwrap() {
+        let solution = sample.cube.solve().unwrap();
+        assert_eq!(sample.distance, solution.len());
+    }
+}
+
+#[test]
+fn ida_is_optimal() {
+    let table = DistanceTable::new(1);
+    let mut cube = RubiksCube::from_scramble("R U F' L");
+    let solution = cube.solve_ida(&table, 4).unwrap().solution;
+    assert_eq!(Some(solution.len()), DistanceTable::new(4).get(&cube));
+
+    cube.apply_scramble(&solution.join(" "));
+    assert!(cube.is_solved());
+}
+
+#[test]
+fn generation_is_deterministic() {
+    let first = generate(&config(Label::ScrambleDepth, 42)).unwrap();
+    let second = generate(&config(Label::ScrambleDepth, 42)).unwrap();
+    assert_eq!(first, second);
+}
+
+#[test]
+fn dataset_round_trip() {
+    let samples = generate(&config(Label::UpperBound, 1)).unwrap();
+    let path = std::env::temp_dir().join("rubiks_dataset_round_trip.csv");
+    write_dataset(&path, &samples, Label::UpperBound).unwrap();
+    assert_eq!(read_dataset(&path).unwrap(), samples);
+}
+
+#[test]
+fn parse_depth_distribution() {
+    assert_eq!(
+        "4".parse::<DepthDistribution>(),
+        Ok(DepthDistribution::Uniform { min: 4, max: 4 })
+    );
+    assert_eq!(
+        "2:1,3:0.5".parse::<DepthDistribution>(),
+        Ok(DepthDistribution::Weighted(vec![(2, 1.0), (3, 0.5)]))
+    );
+    assert!("5-2".parse::<DepthDistribution>().is_err());
+}
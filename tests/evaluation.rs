@@ -0,0 +1,59 @@
+use rubiks::ml::evaluation::{DistanceBucket, EvaluationReport};
+
+#[test]
+fn report_metrics() {
+    let predictions = [0.0, 1.4, 2.6, 2.0, 5.0];
+    let distances = [0, 1, 2, 2, 3];
+    let report = EvaluationReport::new(&predictions, &distances);
+
+    assert_eq!(report.count, 5);
+    assert!((report.mae - (0.4 + 0.6 + 2.0) / 5.0).abs() < 1e-9);
+    assert!((report.overestimates - 3.0 / 5.0).abs() < 1e-9);
+    assert!((report.exact - 3.0 / 5.0).abs() < 1e-9);
+
+    let bucket = &report.buckets[2];
+    assert_eq!((bucket.distance, bucket.count), (2, 2));
+    assert!((bucket.mae - 0.3).abs() < 1e-9);
+    assert!((bucket.overestimates - 0.5).abs() < 1e-9);
+
+    assert_eq!(report.confusion[2][3], 1);
+    assert_eq!(report.confusion[3][5], 1);
+    let bin = report
+        .calibration
+        .iter()
+        .find(|c| c.predicted == 2)
+        .unwrap();
+    assert_eq!(bin.count, 1);
+}
+
+#[test]
+fn report_files() {
+    let report = EvaluationReport::new(&[1.0, 2.5], &[1, 3]);
+    let dir = std::env::temp_dir().join("rubiks_report_files");
+    report.write_csv(&dir).unwrap();
+    let buckets = std::fs::read_to_string(dir.join("buckets.csv")).unwrap();
+    assert_eq!(buckets.lines().count(), 3);
+    assert!(report.to_json().starts_with("{\"count\":2,"));
+}
+
+#[test]
+fn report_json_is_valid_without_samples() {
+    // An empty bucket has no mean, nor has a network that predicts NaN
+    let mut report = EvaluationReport::new(&[f64::NAN], &[1]);
+    report.buckets.push(DistanceBucket {
+        distance: 2,
+        count: 0,
+        mean_prediction: f64::NAN,
+        mae: f64::NAN,
+        rmse: f64::NAN,
+        overestimates: f64::NAN,
+    });
+    let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+    assert!(json["mae"].is_null());
+    assert!(json["buckets"][1]["mean_prediction"].is_null());
+    assert_eq!(json["buckets"][1]["count"], 0);
+
+    let empty = EvaluationReport::new(&[], &[]);
+    let json: serde_json::Value = serde_json::from_str(&empty.to_json()).unwrap();
+    assert_eq!(json["mae"], 0.0);
+}
@@ -0,0 +1,64 @@
+use rubiks::rubiks::cube::RubiksCube;
+
+#[test]
+fn facelet_strings() {
+    let solved = "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB";
+    assert_eq!(RubiksCube::new().to_facelet_string().unwrap(), solved);
+    assert_eq!(
+        RubiksCube::from_facelet_string(solved).unwrap(),
+        RubiksCube::new()
+    );
+
+    // The state after R as Kociemba's solver writes it
+    let mut cube = RubiksCube::from_scramble("R");
+    let r = "UUFUUFUUFRRRRRRRRRFFDFFDFFDDDBDDBDDBLLLLLLLLLUBBUBBUBB";
+    assert_eq!(cube.to_facelet_string().unwrap(), r);
+    assert_eq!(RubiksCube::from_facelet_string(r).unwrap(), cube);
+
+    cube.apply_scramble("U2 F' L D B2 R' D'");
+    let facelets = cube.to_facelet_string().unwrap();
+    assert_eq!(RubiksCube::from_facelet_string(&facelets).unwrap(), cube);
+
+    // Any six symbols work, the centres say which face they stand for
+    let colors: String = facelets
+        .chars()
+        .map(|c| match c {
+            'U' => 'y',
+            'R' => 'o',
+            'F' => 'b',
+            'D' => 'w',
+            'L' => 'r',
+            _ => 'g',
+        })
+        .collect();
+    assert_eq!(RubiksCube::from_facelet_string(&colors).unwrap(), cube);
+}
+
+#[test]
+fn invalid_facelet_strings() {
+    let solved = "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB";
+    assert!(RubiksCube::from_facelet_string(&solved[1..]).is_err());
+    let mut wrong = solved.to_string();
+    wrong.replace_range(0..1, "X");
+    assert!(RubiksCube::from_facelet_string(&wrong).is_err());
+    wrong.replace_range(0..1, "R");
+    assert!(RubiksCube::from_facelet_string(&wrong).is_err());
+    wrong.replace_range(13..14, "U");
+    assert!(RubiksCube::from_facelet_string(&wrong).is_err());
+
+    let mut cube = RubiksCube::new();
+    cube.faces[0][4] = cube.faces[1][4];
+    assert!(cube.to_facelet_string().is_err());
+}
+
+#[test]
+fn state_strings() {
+    let cube = RubiksCube::from_scramble("L F2 U' B");
+    assert_eq!(
+        RubiksCube::from_state_string(&cube.to_string()).unwrap(),
+        cube
+    );
+    assert!(RubiksCube::from_state_string("WWW").is_err());
+    let invalid = cube.to_string().replace('G', "g");
+    assert!(RubiksCube::from_state_string(&invalid).is_err());
+}
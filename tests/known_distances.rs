@@ -0,0 +1,195 @@
+mod common;
+
+use common::table;
+use rubiks::rubiks::astar::AStarConfig;
+use rubiks::rubiks::cube::RubiksCube;
+use rubiks::rubiks::cubie::CubieCube;
+use rubiks::rubiks::kociemba::KociembaTables;
+use rubiks::rubiks::metric::Metric;
+use rubiks::rubiks::moves::parse_algorithm;
+use rubiks::rubiks::solver::SearchResult;
+use rubiks::rubiks::table::DistanceTable;
+
+// Beyond this many quarter turns IDA* takes too long for a debug build
+const DEBUG_DEPTH: usize = 10;
+
+struct Position {
+    name: &'static str,
+    scramble: &'static str,
+    qtm: usize,
+    htm: Option<usize>,
+}
+
+fn corpus() -> Vec<Position> {
+    include_str!("data/known_distances.txt")
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let fields: Vec<&str> = line.split('|').map(str::trim).collect();
+            let [name, scramble, qtm, htm, _source] = fields[..] else {
+                panic!("invalid corpus line {}", line);
+            };
+            Position {
+                name,
+                scramble,
+                qtm: qtm.parse().unwrap(),
+                htm: htm.parse().ok(),
+            }
+        })
+        .collect()
+}
+
+impl Position {
+    fn cube(&self) -> RubiksCube {
+        RubiksCube::from_scramble(self.scramble)
+    }
+
+    fn assert_solves(&self, solver: &str, result: Option<SearchResult>) -> usize {
+        let result = result.unwrap_or_else(|| panic!("{} found nothing for {}", solver, self.name));
+        let mut cube = self.cube();
+        cube.apply_scramble(&result.solution.join(" "));
+        assert!(
+            cube.is_solved(),
+            "{} solution {:?} doesn't solve {}",
+            solver,
+            result.solution,
+            self.name
+        );
+        result.solution.len()
+    }
+
+    fn assert_optimal(&self) {
+        let length = self.assert_solves("IDA*", self.cube().solve_ida(table(), self.qtm));
+        assert_eq!(length, self.qtm, "IDA* on {}", self.name);
+    }
+}
+
+#[test]
+fn corpus_positions_are_what_they_claim() {
+    let corpus = corpus();
+    assert!(corpus.len() >= 6);
+    for position in &corpus {
+        let cube = position.cube();
+        assert!(cube.validate().is_ok(), "{}", position.name);
+        // A distance can't be longer than the scramble that reaches it
+        let moves = position.scramble.split_whitespace();
+        let quarter_turns: usize = moves.clone().map(|m| 1 + m.ends_with('2') as usize).sum();
+        assert!(position.qtm <= quarter_turns, "{}", position.name);
+        if let Some(htm) = position.htm {
+            assert!(
+                htm <= moves.count() && htm <= position.qtm,
+                "{}",
+                position.name
+            );
+        }
+    }
+
+    // Every edge flipped in place, corners solved
+    let superflip = corpus.iter().find(|p| p.name == "superflip").unwrap();
+    let cubie = superflip.cube().validate().unwrap();
+    let solved = CubieCube::new();
+    assert_eq!(cubie.corner_perm, solved.corner_perm);
+    assert_eq!(cubie.corner_orient, solved.corner_orient);
+    assert_eq!(cubie.edge_perm, solved.edge_perm);
+    assert_eq!(cubie.edge_orient, [1; 12]);
+}
+
+#[test]
+fn optimal_solvers_find_the_known_distance() {
+    for position in corpus().iter().filter(|p| p.qtm <= DEBUG_DEPTH) {
+        position.assert_optimal();
+        if position.qtm <= 5 {
+            let length = position.assert_solves("BFS", position.cube().solve_bfs());
+            assert_eq!(length, position.qtm, "BFS on {}", position.name);
+        }
+    }
+}
+
+#[test]
+fn optimal_solvers_find_the_known_face_turn_distance() {
+    let table = DistanceTable::with_metric(4, Metric::Htm);
+    let known = corpus().into_iter().filter_map(|p| Some((p.htm?, p)));
+    for (htm, position) in known.filter(|&(htm, _)| htm <= 8) {
+        let result = position.cube().solve_ida(&table, htm);
+        position.assert_solves("IDA* in HTM", result.clone());
+        let solution = parse_algorithm(&result.unwrap().solution.join(" ")).unwrap();
+        assert_eq!(Metric::Htm.length(&solution), htm, "{}", position.name);
+    }
+}
+
+#[test]
+fn weighted_astar_finds_valid_solutions() {
+    // Greedier than A* and so not optimal, but never shorter than optimal.
+    // MCTS is left out, it needs a trained model.
+    let config = AStarConfig {
+        weight: 2.0,
+        ..AStarConfig::default()
+    };
+    for position in corpus().iter().filter(|p| p.qtm <= DEBUG_DEPTH) {
+        let result = position.cube().solve_weighted_astar(table(), &config);
+        let length = position.assert_solves("weighted A*", result);
+        assert!(length >= position.qtm, "weighted A* on {}", position.name);
+    }
+}
+
+// Pons asinorum and four spot
+#[test]
+#[ignore = "half a minute in debug builds, run with cargo test --release -- --ignored"]
+fn optimal_solvers_find_the_known_distance_of_deeper_positions() {
+    let corpus = corpus();
+    let deeper = corpus.iter().filter(|p| p.qtm > DEBUG_DEPTH && p.qtm <= 12);
+    for position in deeper {
+        position.assert_optimal();
+    }
+}
+
+// Superflip's 20 face turns were proven by Reid with a computer search far
+// beyond what this crate can do in a test. What can be checked: the corpus
+// maneuver is 20 face turns, so 20 is an upper bound; two-phase, which is
+// not optimal, never beats it; and IDA* in HTM rules out every solution of
+// up to LOWER_BOUND face turns.
+#[test]
+fn superflip_takes_twenty_face_turns() {
+    const LOWER_BOUND: usize = 8;
+    let corpus = corpus();
+    let superflip = corpus.iter().find(|p| p.name == "superflip").unwrap();
+    assert_eq!(superflip.htm, Some(20));
+    assert_eq!(superflip.scramble.split_whitespace().count(), 20);
+
+    let tables = KociembaTables::new();
+    let length = superflip.assert_solves("two-phase", superflip.cube().solve_kociemba(&tables, 24));
+    assert!(length >= 20, "two-phase beat the proof with {}", length);
+
+    let table = DistanceTable::with_metric(4, Metric::Htm);
+    assert!(superflip.cube().solve_ida(&table, LOWER_BOUND).is_none());
+}
+
+// Number of states at every distance from solved, as counted by Rokicki et
+// al. (OEIS A080583 for quarter turns, A080601 for face turns). The tables
+// the heuristics look up have to agree with them.
+#[test]
+fn distance_tables_count_the_published_number_of_states() {
+    let cumulative = |counts: &[usize]| -> Vec<usize> {
+        counts
+            .iter()
+            .scan(0, |sum, &count| {
+                *sum += count;
+                Some(*sum)
+            })
+            .collect()
+    };
+    let qtm = cumulative(&[1, 12, 114, 1068, 10011, 93840]);
+    assert_eq!(table().len(), qtm[5]);
+    for (depth, &len) in qtm.iter().enumerate().take(4) {
+        assert_eq!(DistanceTable::new(depth).len(), len, "qtm {}", depth);
+    }
+    let htm = cumulative(&[1, 18, 243, 3240, 43239]);
+    for (depth, &len) in htm.iter().enumerate() {
+        assert_eq!(
+            DistanceTable::with_metric(depth, Metric::Htm).len(),
+            len,
+            "htm {}",
+            depth
+        );
+    }
+}
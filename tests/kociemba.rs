@@ -0,0 +1,65 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rubiks::rubiks::cube::RubiksCube;
+use rubiks::rubiks::kociemba::KociembaTables;
+use rubiks::rubiks::moves::{algorithm_to_string, generate_scramble};
+
+const SUPERFLIP: &str = "U R2 F B R B2 R U2 L B2 R U' D' R2 F R' L B2 U2 F2";
+
+fn solves(scramble: &str, solution: &[String]) -> bool {
+    RubiksCube::from_scramble(&format!("{} {}", scramble, solution.join(" "))).is_solved()
+}
+
+// The tables take a while to build, so all cases share one test
+#[test]
+fn two_phase_solves_deep_scrambles() {
+    let tables = KociembaTables::new();
+
+    let mut rng = StdRng::seed_from_u64(7);
+    for _ in 0..5 {
+        let scramble = algorithm_to_string(&generate_scramble(&mut rng, 30));
+        let result = RubiksCube::from_scramble(&scramble)
+            .solve_kociemba(&tables, 30)
+            .unwrap_or_else(|| panic!("{}", scramble));
+        assert!(result.solution.len() <= 30);
+        assert!(solves(&scramble, &result.solution));
+        assert!(result.nodes_expanded > 0);
+    }
+
+    // No solution is shorter than the 20 face turns superflip is known to take
+    let result = RubiksCube::from_scramble(SUPERFLIP)
+        .solve_kociemba(&tables, 24)
+        .unwrap();
+    assert!((20..=24).contains(&result.solution.len()));
+    assert!(solves(SUPERFLIP, &result.solution));
+
+    // The first solution within the limit is returned, so a tight limit
+    // gives short solutions
+    let cube = RubiksCube::from_scramble("R U F'");
+    assert!(solves(
+        "R U F'",
+        &cube.solve_kociemba(&tables, 30).unwrap().solution
+    ));
+    let result = cube.solve_kociemba(&tables, 3).unwrap();
+    assert_eq!(result.solution, ["F", "U'", "R'"]);
+    assert!(cube.solve_kociemba(&tables, 2).is_none());
+
+    assert_eq!(
+        RubiksCube::new()
+            .solve_kociemba(&tables, 30)
+            .unwrap()
+            .solution,
+        Vec::<String>::new()
+    );
+    // A single corner twisted in place
+    let mut twisted = RubiksCube::new();
+    let urf = [
+        twisted.faces[0][8],
+        twisted.faces[4][0],
+        twisted.faces[2][2],
+    ];
+    twisted.faces[0][8] = urf[1];
+    twisted.faces[4][0] = urf[2];
+    twisted.faces[2][2] = urf[0];
+    assert!(twisted.solve_kociemba(&tables, 30).is_none());
+}
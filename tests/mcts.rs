@@ -0,0 +1,37 @@
+use rubiks::ml::neural_network::NeuralNetwork;
+use rubiks::rubiks::cube::RubiksCube;
+use rubiks::rubiks::mcts::MctsConfig;
+
+#[test]
+fn mcts_finds_valid_solution() {
+    let network = NeuralNetwork::with_seed(8, 2);
+    let mut cube = RubiksCube::from_scramble("R U'");
+    let result = cube.solve_mcts(&network, &MctsConfig::default()).unwrap();
+    assert!(result.solution.len() <= 2);
+    assert!(result.tree_size > 1);
+
+    cube.apply_scramble(&result.solution.join(" "));
+    assert!(cube.is_solved());
+}
+
+#[test]
+fn mcts_is_deterministic_under_seed() {
+    let network = NeuralNetwork::with_seed(8, 2);
+    let cube = RubiksCube::from_scramble("F D");
+    let config = MctsConfig {
+        seed: 5,
+        ..MctsConfig::default()
+    };
+    assert_eq!(
+        cube.solve_mcts(&network, &config),
+        cube.solve_mcts(&network, &config)
+    );
+}
+
+#[test]
+fn policy_is_a_distribution() {
+    let network = NeuralNetwork::with_seed(8, 2);
+    let policy = network.policy(&RubiksCube::new());
+    assert_eq!(policy.len(), 12);
+    assert!((policy.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+}
@@ -0,0 +1,114 @@
+use rubiks::rubiks::astar::AStarConfig;
+use rubiks::rubiks::cube::RubiksCube;
+use rubiks::rubiks::metric::Metric;
+use rubiks::rubiks::moves::parse_algorithm;
+use rubiks::rubiks::solver::SearchResult;
+use rubiks::rubiks::table::DistanceTable;
+use std::collections::HashSet;
+
+// Lengths in QTM, HTM, STM and ATM
+fn lengths(moves: &str) -> [usize; 4] {
+    let moves = parse_algorithm(moves).unwrap();
+    Metric::ALL.map(|metric| metric.length(&moves))
+}
+
+fn assert_solves(cube: &RubiksCube, result: Option<SearchResult>) -> Vec<String> {
+    let solution = result.expect("a solution").solution;
+    let mut solved = cube.clone();
+    solved.apply_scramble(&solution.join(" "));
+    assert!(solved.is_solved(), "{:?}", solution);
+    solution
+}
+
+#[test]
+fn metrics_parse_and_have_their_moves() {
+    for metric in Metric::ALL {
+        assert_eq!(metric.name().parse::<Metric>(), Ok(metric));
+    }
+    assert_eq!("HTM".parse::<Metric>(), Ok(Metric::Htm));
+    assert!("otm".parse::<Metric>().is_err());
+
+    for (metric, count) in Metric::ALL.into_iter().zip([12, 18, 27, 45]) {
+        let moves = metric.moves();
+        assert_eq!(moves.len(), count, "{}", metric);
+        // Every move leads somewhere else, and no two to the same state
+        let states: HashSet<RubiksCube> = moves
+            .iter()
+            .map(|m| {
+                let mut cube = RubiksCube::new();
+                m.apply(&mut cube);
+                assert!(!cube.is_solved(), "{}", m.name);
+                cube
+            })
+            .collect();
+        assert_eq!(states.len(), count, "{}", metric);
+        for m in &moves {
+            assert!(moves.contains(&m.inverse()), "{} in {}", m.name, metric);
+        }
+    }
+
+    let names: Vec<String> = Metric::Qtm.moves().into_iter().map(|m| m.name).collect();
+    let all_moves: Vec<&str> = RubiksCube::new()
+        .all_moves()
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    assert_eq!(names, all_moves);
+}
+
+#[test]
+fn lengths_in_every_metric() {
+    assert_eq!(lengths(""), [0, 0, 0, 0]);
+    assert_eq!(lengths("R U R' U'"), [4, 4, 4, 4]);
+    assert_eq!(lengths("R2"), [2, 1, 1, 1]);
+    assert_eq!(lengths("R L'"), [2, 2, 1, 1]);
+    assert_eq!(lengths("R2 L2"), [4, 2, 1, 1]);
+    assert_eq!(lengths("R L"), [2, 2, 2, 1]);
+    assert_eq!(lengths("U2 D"), [3, 2, 2, 1]);
+    // Turns of the same axis combine, also across a run that cancels out
+    assert_eq!(lengths("R R"), [2, 1, 1, 1]);
+    assert_eq!(lengths("R L R"), [3, 2, 2, 1]);
+    assert_eq!(lengths("R U U' R"), [2, 1, 1, 1]);
+    assert_eq!(lengths("R R'"), [0, 0, 0, 0]);
+    assert_eq!(lengths("U R2 F' D2 B"), [7, 5, 5, 5]);
+}
+
+#[test]
+fn bfs_is_optimal_in_every_metric() {
+    let cube = RubiksCube::from_scramble("R2 L'");
+    for (metric, optimal) in Metric::ALL.into_iter().zip(lengths("R2 L'")) {
+        let solution = assert_solves(&cube, cube.solve_bfs_with(metric));
+        let moves = parse_algorithm(&solution.join(" ")).unwrap();
+        assert_eq!(metric.length(&moves), optimal, "{}", metric);
+    }
+    assert_eq!(cube.solve_bfs(), cube.solve_bfs_with(Metric::Qtm));
+}
+
+#[test]
+fn ida_and_astar_search_in_the_metric_of_their_table() {
+    let cube = RubiksCube::from_scramble("U2 R2 F' D B2");
+    let table = DistanceTable::with_metric(3, Metric::Htm);
+    assert_eq!(table.metric(), Metric::Htm);
+    // 1 + 18 + 243 + 3240 states within 3 face turns
+    assert_eq!(table.len(), 3502);
+
+    let solution = assert_solves(&cube, cube.solve_ida(&table, 5));
+    assert_eq!(solution.len(), 5);
+    assert!(cube.solve_ida(&table, 4).is_none());
+
+    let config = AStarConfig {
+        metric: Metric::Htm,
+        ..AStarConfig::default()
+    };
+    let solution = assert_solves(&cube, cube.solve_weighted_astar(&table, &config));
+    assert_eq!(solution.len(), 5);
+
+    // A slice turn and an axial turn are one move each
+    let cube = RubiksCube::from_scramble("R L' U2 D");
+    for (metric, optimal) in [(Metric::Stm, 3), (Metric::Atm, 2)] {
+        let table = DistanceTable::with_metric(1, metric);
+        let solution = assert_solves(&cube, cube.solve_ida(&table, 4));
+        let moves = parse_algorithm(&solution.join(" ")).unwrap();
+        assert_eq!(metric.length(&moves), optimal, "{}", metric);
+    }
+}
@@ -0,0 +1,37 @@
+use rubiks::rubiks::cube::RubiksCube;
+use rubiks::rubiks::moves::{
+    algorithm_to_string, invert_algorithm, parse_algorithm, Face, Move, Turn,
+};
+
+#[test]
+fn parse_and_display_round_trip() {
+    let algorithm = parse_algorithm("R U R' U' F2 B2'").unwrap();
+    assert_eq!(algorithm[2], Move::new(Face::R, Turn::CounterClockwise));
+    assert_eq!(algorithm[5], Move::new(Face::B, Turn::Double));
+    assert_eq!(algorithm_to_string(&algorithm), "R U R' U' F2 B2");
+    for m in Move::all() {
+        assert_eq!(m.to_string().parse::<Move>(), Ok(m));
+    }
+    assert!(parse_algorithm("R X U").is_err());
+    assert!(parse_algorithm("R3").is_err());
+}
+
+#[test]
+fn moves_agree_with_apply_scramble() {
+    let scramble = "R U' F2 D L' B2 U";
+    let mut expected = RubiksCube::new();
+    expected.apply_scramble(scramble);
+    let mut cube = RubiksCube::new();
+    cube.apply_moves(&parse_algorithm(scramble).unwrap());
+    assert_eq!(cube, expected);
+}
+
+#[test]
+fn inverse_algorithm_solves() {
+    let algorithm = parse_algorithm("F R2 U' L D B'").unwrap();
+    let mut cube = RubiksCube::new();
+    cube.apply_moves(&algorithm);
+    assert!(!cube.is_solved());
+    cube.apply_moves(&invert_algorithm(&algorithm));
+    assert!(cube.is_solved());
+}
@@ -0,0 +1,63 @@
+use rubiks::ml::neural_network::NeuralNetwork;
+use rubiks::ml::value_iteration::{bellman_target, train_value_iteration, ValueIterationConfig};
+use rubiks::rubiks::cube::RubiksCube;
+
+#[test]
+fn save_and_load() {
+    let network = NeuralNetwork::with_seed(4, 3);
+    let path = std::env::temp_dir().join("rubiks_network_save_and_load.txt");
+    network.save(&path).unwrap();
+    assert_eq!(NeuralNetwork::load(&path).unwrap(), network);
+}
+
+#[test]
+fn bellman_target_near_solved() {
+    let network = NeuralNetwork::with_seed(4, 3);
+    assert_eq!(bellman_target(&RubiksCube::new(), &network), 0.0);
+
+    let cube = RubiksCube::from_scramble("F'");
+    assert_eq!(bellman_target(&cube, &network), 1.0);
+}
+
+#[test]
+fn value_iteration_is_deterministic() {
+    let config = ValueIterationConfig {
+        iterations: 5,
+        batch_size: 20,
+        max_depth: 4,
+        epochs: 1,
+        update_interval: 2,
+        train_policy: true,
+        seed: 9,
+    };
+    // The weight noise is drawn from the seeded generator as well
+    let train = || {
+        let mut network = NeuralNetwork::with_seed(4, 9);
+        let mut updates = 0;
+        train_value_iteration(&mut network, &config, |stats| {
+            updates += stats.target_updated as usize
+        })
+        .unwrap();
+        assert_eq!(updates, 2);
+        network
+    };
+    assert_eq!(train(), train());
+
+    for config in [
+        ValueIterationConfig {
+            batch_size: 0,
+            ..config
+        },
+        ValueIterationConfig {
+            max_depth: 0,
+            ..config
+        },
+        ValueIterationConfig {
+            update_interval: 0,
+            ..config
+        },
+    ] {
+        let mut network = NeuralNetwork::with_seed(4, 9);
+        assert!(train_value_iteration(&mut network, &config, |_| {}).is_err());
+    }
+}
@@ -0,0 +1,82 @@
+use rubiks::rubiks::cube::RubiksCube;
+use rubiks::rubiks::cubie::CubieCube;
+use rubiks::rubiks::moves::parse_algorithm;
+use rubiks::rubiks::permutation::CycleStructure;
+
+fn cycles(algorithm: &str) -> CycleStructure {
+    CubieCube::from_moves(&parse_algorithm(algorithm).unwrap()).cycles()
+}
+
+// Repeats the algorithm until the cube is solved again
+fn brute_force_order(algorithm: &str) -> usize {
+    let state = CubieCube::from_moves(&parse_algorithm(algorithm).unwrap());
+    let mut cube = state;
+    let mut order = 1;
+    while cube != CubieCube::new() {
+        cube = cube.multiply(&state);
+        order += 1;
+    }
+    order
+}
+
+#[test]
+fn cycles_of_single_turns_and_pll() {
+    let r = cycles("R");
+    assert_eq!(r.to_string(), "(URF UBR DRB DFR) (UR BR DR FR)");
+    assert_eq!(r.order(), 4);
+    assert!(r.is_odd());
+    assert_eq!(r.affected_corners(), vec![0, 3, 4, 7]);
+    assert_eq!(r.affected_edges(), vec![0, 4, 8, 11]);
+    assert_eq!(cycles("R'").to_string(), "(URF DFR DRB UBR) (UR FR DR BR)");
+
+    let t_perm = cycles("R U R' U' R' F R2 U' R' U' R U R' F'");
+    assert_eq!(t_perm.to_string(), "(URF UBR) (UR UL)");
+    assert_eq!(t_perm.order(), 2);
+    assert!(t_perm.is_odd());
+
+    let solved = cycles("");
+    assert_eq!(solved, CycleStructure::default());
+    assert_eq!(solved.to_string(), "()");
+    assert_eq!(solved.order(), 1);
+    assert!(!solved.is_odd());
+    assert!(solved.affected_corners().is_empty());
+}
+
+#[test]
+fn twists_and_flips_count_towards_the_order() {
+    let superflip = cycles("U R2 F B R B2 R U2 L B2 R U' D' R2 F R' L B2 U2 F2");
+    assert!(superflip.corners.is_empty());
+    assert_eq!(superflip.edges.len(), 12);
+    assert!(superflip.edges.iter().all(|c| c.positions.len() == 1 && c.twist == 1));
+    assert!(superflip.to_string().starts_with("(UR)+ (UF)+ (UL)+"));
+    assert_eq!(superflip.order(), 2);
+    assert!(!superflip.is_odd());
+
+    // Sune turns three corners around and twists them on the way
+    let sune = cycles("R U R' U R U2 R'");
+    assert_eq!(sune.order(), 6);
+    assert_eq!(sune.affected_corners().len(), 4);
+    assert_eq!(sune.affected_edges().len(), 3);
+
+    for (algorithm, order) in [("R U", 105), ("R U2 D' B D'", 1260), ("R U R' U'", 6)] {
+        assert_eq!(cycles(algorithm).order(), order, "{}", algorithm);
+        assert_eq!(brute_force_order(algorithm), order, "{}", algorithm);
+    }
+    for algorithm in ["F R' U2 L", "R2 D B' L U F2", "U F' R2 D' L B"] {
+        assert_eq!(
+            cycles(algorithm).order(),
+            brute_force_order(algorithm),
+            "{}",
+            algorithm
+        );
+    }
+}
+
+#[test]
+fn states_have_the_cycles_of_their_algorithm() {
+    let mut cube = RubiksCube::from_scramble("R U R' U' F2 L D'");
+    assert_eq!(cube.cycles(), Ok(cycles("R U R' U' F2 L D'")));
+
+    cube.faces[0].swap(1, 3);
+    assert!(cube.cycles().is_err());
+}
@@ -0,0 +1,77 @@
+use rubiks::rubiks::cube::RubiksCube;
+use rubiks::rubiks::moves::parse_algorithm;
+use rubiks::ui::player::{solve, SolutionPlayer, SolveJob, SolverChoice, SolverSettings};
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn every_solver_solves() {
+    let cube = RubiksCube::from_scramble("R U' F");
+    for solver in SolverChoice::ALL {
+        let settings = SolverSettings {
+            solver,
+            table_depth: 2,
+            ..SolverSettings::default()
+        };
+        let solution = solve(&cube, &settings).unwrap();
+        let mut solved = cube.clone();
+        solved.apply_moves(&solution.moves);
+        assert!(
+            solved.is_solved(),
+            "{} gave a wrong solution",
+            solver.name()
+        );
+    }
+}
+
+#[test]
+fn background_job_reports_the_solution() {
+    let job = SolveJob::spawn(RubiksCube::from_scramble("L D"), SolverSettings::default());
+    let result = loop {
+        if let Some(result) = job.poll() {
+            break result;
+        }
+        thread::sleep(Duration::from_millis(10));
+    };
+    assert_eq!(result.unwrap().moves, parse_algorithm("D' L'").unwrap());
+}
+
+#[test]
+fn player_steps_through_intermediate_states() {
+    let start = RubiksCube::from_scramble("F R U");
+    let moves = parse_algorithm("U' R' F'").unwrap();
+    let mut player = SolutionPlayer::new(start.clone(), moves.clone());
+    assert_eq!(player.current_move(), None);
+
+    assert_eq!(player.step_forward(), Some(moves[0]));
+    assert_eq!(player.step_forward(), Some(moves[1]));
+    assert_eq!(player.current_move(), Some(moves[1]));
+    assert_eq!(player.cube(), &RubiksCube::from_scramble("F"));
+
+    assert_eq!(player.step_back(), Some(moves[1].inverse()));
+    assert_eq!(player.cube(), &RubiksCube::from_scramble("F R"));
+
+    player.jump_to(3);
+    assert!(player.is_finished());
+    assert!(player.cube().is_solved());
+    assert_eq!(player.step_forward(), None);
+
+    player.jump_to(0);
+    assert_eq!(player.cube(), &start);
+    assert_eq!(player.step_back(), None);
+}
+
+#[test]
+fn playback_waits_for_the_interval() {
+    let moves = parse_algorithm("R' U'").unwrap();
+    let mut player = SolutionPlayer::new(RubiksCube::from_scramble("U R"), moves.clone());
+    assert_eq!(player.update(1.0, 0.5), None);
+
+    player.playing = true;
+    assert_eq!(player.update(0.3, 0.5), None);
+    assert_eq!(player.update(0.3, 0.5), Some(moves[0]));
+    assert_eq!(player.update(0.6, 0.5), Some(moves[1]));
+    assert_eq!(player.update(0.6, 0.5), None);
+    assert!(!player.playing);
+    assert!(player.cube().is_solved());
+}
@@ -0,0 +1,142 @@
+// Properties of the face turns over random move sequences. They check the
+// hand-written sticker tables of RubiksCube against each other and against
+// the cubie model, which turns the pieces from an independent definition.
+mod common;
+
+use common::table;
+use proptest::prelude::*;
+use rubiks::rubiks::astar::AStarConfig;
+use rubiks::rubiks::color::Color;
+use rubiks::rubiks::cube::RubiksCube;
+use rubiks::rubiks::cubie::CubieCube;
+use rubiks::rubiks::moves::{algorithm_to_string, invert_algorithm, Face, Move, Turn};
+
+fn any_move() -> impl Strategy<Value = Move> {
+    (0..18usize).prop_map(|i| Move::all()[i])
+}
+
+fn any_sequence(max_length: usize) -> impl Strategy<Value = Vec<Move>> {
+    prop::collection::vec(any_move(), 0..=max_length)
+}
+
+// Quarter turns only, which is what the solvers use
+fn quarter_turns(max_length: usize) -> impl Strategy<Value = Vec<Move>> {
+    let quarter = (0..6usize, any::<bool>()).prop_map(|(face, clockwise)| {
+        let turn = if clockwise {
+            Turn::Clockwise
+        } else {
+            Turn::CounterClockwise
+        };
+        Move::new(Face::ALL[face], turn)
+    });
+    prop::collection::vec(quarter, 0..=max_length)
+}
+
+proptest! {
+    #[test]
+    fn every_move_has_order_four(scramble in any_sequence(25), m in any_move()) {
+        let start = RubiksCube::from_moves(&scramble);
+        let mut cube = start.clone();
+        for turns in 1..=4 {
+            cube.apply_move(m);
+            // A quarter turn only comes back after four, a half turn after two
+            let back = turns == 4 || (turns == 2 && m.turn == Turn::Double);
+            prop_assert_eq!(cube == start, back, "{} applied {} times", m, turns);
+        }
+    }
+
+    #[test]
+    fn move_fns_are_inverse_pairs(scramble in any_sequence(25)) {
+        let start = RubiksCube::from_moves(&scramble);
+        let moves = RubiksCube::new().all_moves();
+        // all_moves lists every turn followed by its inverse
+        for pair in moves.chunks(2) {
+            let mut cube = start.clone();
+            (pair[0].1)(&mut cube);
+            prop_assert_ne!(&cube, &start);
+            (pair[1].1)(&mut cube);
+            prop_assert_eq!(&cube, &start, "{} {}", pair[0].0, pair[1].0);
+        }
+    }
+
+    #[test]
+    fn inverse_sequence_solves(scramble in any_sequence(40)) {
+        let mut cube = RubiksCube::from_moves(&scramble);
+        cube.apply_moves(&invert_algorithm(&scramble));
+        prop_assert!(cube.is_solved());
+    }
+
+    #[test]
+    fn notation_and_moves_agree(scramble in any_sequence(25)) {
+        prop_assert_eq!(
+            RubiksCube::from_scramble(&algorithm_to_string(&scramble)),
+            RubiksCube::from_moves(&scramble)
+        );
+    }
+
+    #[test]
+    fn sticker_counts_and_centres_are_kept(scramble in any_sequence(40)) {
+        let cube = RubiksCube::from_moves(&scramble);
+        for color in Color::ALL {
+            let count = cube.faces.iter().flatten().filter(|&&c| c == color).count();
+            prop_assert_eq!(count, 9, "{:?}", color);
+        }
+        for (face, solved) in cube.faces.iter().zip(RubiksCube::new().faces) {
+            prop_assert_eq!(face[4], solved[4]);
+        }
+    }
+
+    #[test]
+    fn opposite_faces_commute(scramble in any_sequence(25), a in any_move(), b in any_move()) {
+        let b = Move::new(a.face.opposite(), b.turn);
+        let mut ab = RubiksCube::from_moves(&scramble);
+        ab.apply_moves(&[a, b]);
+        let mut ba = RubiksCube::from_moves(&scramble);
+        ba.apply_moves(&[b, a]);
+        prop_assert_eq!(ab, ba);
+    }
+
+    #[test]
+    fn cubie_and_facelet_models_agree(scramble in any_sequence(40)) {
+        let mut cubie = CubieCube::new();
+        for &m in &scramble {
+            cubie.apply_move(m);
+        }
+        let cube = RubiksCube::from_moves(&scramble);
+        prop_assert_eq!(CubieCube::from_facelets(&cube), Ok(cubie));
+        prop_assert_eq!(cubie.to_facelets(), cube);
+    }
+}
+
+proptest! {
+    // The searches are slow, so fewer and shorter cases
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn solutions_solve(scramble in quarter_turns(5)) {
+        let cube = RubiksCube::from_moves(&scramble);
+        let solutions = [
+            cube.solve_ida(table(), 5),
+            cube.solve_weighted_astar(table(), &AStarConfig::default()),
+        ];
+        for result in solutions {
+            let solution = result.expect("a solution within the limits");
+            let mut solved = cube.clone();
+            solved.apply_scramble(&solution.solution.join(" "));
+            prop_assert!(solved.is_solved(), "{:?}", solution.solution);
+        }
+        // IDA* is optimal
+        let optimal = cube.solve_ida(table(), 5).unwrap().solution.len();
+        prop_assert!(optimal <= scramble.len());
+    }
+
+    #[test]
+    fn bfs_solutions_solve(scramble in quarter_turns(3)) {
+        let cube = RubiksCube::from_moves(&scramble);
+        let solution = cube.solve().expect("BFS always finds a solution");
+        let mut solved = cube.clone();
+        solved.apply_scramble(&solution.join(" "));
+        prop_assert!(solved.is_solved());
+        prop_assert!(solution.len() <= scramble.len());
+    }
+}
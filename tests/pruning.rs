@@ -0,0 +1,98 @@
+use rubiks::rubiks::cube::RubiksCube;
+use rubiks::rubiks::metric::Metric;
+use rubiks::rubiks::pruning::{SuccessorGenerator, START};
+use rubiks::rubiks::table::DistanceTable;
+use std::collections::HashSet;
+
+// Number of canonical sequences of every length up to `depth`
+fn sequences(generator: &SuccessorGenerator, depth: usize) -> Vec<f64> {
+    let mut counts = vec![0.0; generator.states()];
+    counts[START] = 1.0;
+    let mut totals = vec![1.0];
+    for _ in 0..depth {
+        let mut next = vec![0.0; generator.states()];
+        for (state, &count) in counts.iter().enumerate() {
+            for (_, _, next_state) in generator.successors(state) {
+                next[next_state] += count;
+            }
+        }
+        totals.push(next.iter().sum());
+        counts = next;
+    }
+    totals
+}
+
+// Every state the canonical sequences of at most `depth` moves reach
+fn reached(generator: &SuccessorGenerator, depth: usize) -> HashSet<RubiksCube> {
+    let mut reached = HashSet::new();
+    let mut stack = vec![(RubiksCube::new(), START, 0)];
+    while let Some((cube, state, length)) = stack.pop() {
+        reached.insert(cube.clone());
+        if length == depth {
+            continue;
+        }
+        for (_, m, next_state) in generator.successors(state) {
+            let mut next_cube = cube.clone();
+            m.apply(&mut next_cube);
+            stack.push((next_cube, next_state, length + 1));
+        }
+    }
+    reached
+}
+
+#[test]
+fn branching_factor_drops() {
+    let branching = |metric| {
+        let totals = sequences(&SuccessorGenerator::new(metric), 20);
+        totals[20] / totals[19]
+    };
+    assert!((branching(Metric::Htm) - 13.35).abs() < 0.01);
+    assert!((branching(Metric::Qtm) - 9.37).abs() < 0.01);
+
+    // The first move is free, after it no face is turned twice in HTM
+    let generator = SuccessorGenerator::new(Metric::Htm);
+    assert_eq!(generator.successors(START).count(), 18);
+    for (_, m, state) in generator.successors(START) {
+        let next = generator.successors(state).count();
+        assert!(next == 15 || next == 12, "{} {}", m.name, next);
+        assert!(generator
+            .successors(state)
+            .all(|(_, n, _)| n.turns[0].face != m.turns[0].face));
+    }
+    // As many canonical sequences of two face turns as states two moves away
+    assert_eq!(sequences(&generator, 2)[2], 243.0);
+}
+
+#[test]
+fn canonical_sequences_reach_every_state() {
+    for (metric, depth) in [
+        (Metric::Qtm, 4),
+        (Metric::Htm, 3),
+        (Metric::Stm, 3),
+        (Metric::Atm, 3),
+    ] {
+        let generator = SuccessorGenerator::new(metric);
+        let all = DistanceTable::with_metric(depth, metric);
+        // Without pruning the same BFS
+        let mut expected = HashSet::from([RubiksCube::new()]);
+        let mut frontier = vec![RubiksCube::new()];
+        for _ in 0..depth {
+            let mut next = Vec::new();
+            for cube in &frontier {
+                for m in generator.moves() {
+                    let mut next_cube = cube.clone();
+                    m.apply(&mut next_cube);
+                    if expected.insert(next_cube.clone()) {
+                        next.push(next_cube);
+                    }
+                }
+            }
+            frontier = next;
+        }
+        let reached = reached(&generator, depth);
+        assert_eq!(reached, expected, "{}", metric);
+        assert_eq!(all.len(), expected.len(), "{}", metric);
+    }
+    // 1, 12, 114, 1068 and 10011 states within 0 to 4 quarter turns
+    assert_eq!(DistanceTable::new(4).len(), 11206);
+}
@@ -0,0 +1,108 @@
+use rubiks::render::{pll_arrows, render, Arrow, RenderOptions, Shape, View};
+use rubiks::rubiks::cases::PLL_CASES;
+use rubiks::rubiks::cube::RubiksCube;
+use rubiks::ui::scheme::ColorScheme;
+
+fn polygons(shapes: &[Shape]) -> usize {
+    shapes
+        .iter()
+        .filter(|shape| matches!(shape, Shape::Polygon { .. }))
+        .count()
+}
+
+#[test]
+fn views_draw_every_visible_sticker() {
+    let cube = RubiksCube::new();
+    let diagram = |view| {
+        render(
+            &cube,
+            &RenderOptions {
+                view,
+                ..RenderOptions::default()
+            },
+        )
+    };
+    assert_eq!(polygons(&diagram(View::Net).shapes), 54);
+    assert_eq!(polygons(&diagram(View::Isometric).shapes), 27);
+    assert_eq!(polygons(&diagram(View::LastLayer).shapes), 21);
+    for view in View::ALL {
+        assert_eq!(view.name().parse(), Ok(view));
+    }
+    assert!("top".parse::<View>().is_err());
+}
+
+#[test]
+fn pll_arrows_follow_the_pieces() {
+    let solved = RubiksCube::new();
+    assert!(pll_arrows(&solved).is_empty());
+
+    // Ua perm: three edges cycle, no corner moves
+    let ua = PLL_CASES.iter().find(|case| case.name == "Ua").unwrap();
+    let arrows = pll_arrows(&ua.setup(0, 0));
+    assert_eq!(arrows.len(), 3);
+    assert!(arrows
+        .iter()
+        .all(|arrow| !arrow.both && arrow.from % 2 == 1));
+
+    // H perm: two pairs of opposite edges swap
+    let h = PLL_CASES.iter().find(|case| case.name == "H").unwrap();
+    let mut arrows = pll_arrows(&h.setup(0, 0));
+    arrows.sort_by_key(|arrow| arrow.from);
+    assert_eq!(
+        arrows,
+        [
+            Arrow {
+                from: 5,
+                to: 3,
+                both: true
+            },
+            Arrow {
+                from: 7,
+                to: 1,
+                both: true
+            },
+        ]
+    );
+}
+
+#[test]
+fn write_svg_and_png() {
+    let t = PLL_CASES.iter().find(|case| case.name == "T").unwrap();
+    let cube = t.setup(0, 0);
+    let options = RenderOptions {
+        view: View::LastLayer,
+        scheme: ColorScheme::color_blind(),
+        arrows: pll_arrows(&cube),
+        ..RenderOptions::default()
+    };
+    let diagram = render(&cube, &options);
+    assert!(diagram
+        .shapes
+        .iter()
+        .any(|shape| matches!(shape, Shape::Arrow { both: true, .. })));
+
+    let svg = diagram.to_svg();
+    assert!(svg.starts_with("<svg"));
+    assert_eq!(svg.matches("<text").count(), 21);
+    assert!(svg.trim_end().ends_with("</svg>"));
+
+    let dir = std::env::temp_dir().join("rubiks_render");
+    for name in ["t_perm.svg", "t_perm.png"] {
+        diagram.save(dir.join(name)).unwrap();
+    }
+    let png = std::fs::read(dir.join("t_perm.png")).unwrap();
+    assert_eq!(&png[1..4], b"PNG");
+    assert!(diagram.save(dir.join("t_perm.gif")).is_err());
+
+    for view in [View::Net, View::Isometric] {
+        let mut cube = RubiksCube::new();
+        cube.apply_scramble("R U F'");
+        let options = RenderOptions {
+            view,
+            ..RenderOptions::default()
+        };
+        render(&cube, &options)
+            .save(dir.join(format!("{}.png", view.name())))
+            .unwrap();
+    }
+}
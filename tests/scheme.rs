@@ -0,0 +1,66 @@
+use eframe::egui::Color32;
+use rubiks::rubiks::color::Color;
+use rubiks::ui::scheme::{parse_schemes, ColorScheme};
+use std::collections::HashSet;
+
+#[test]
+fn builtin_schemes_use_six_colours() {
+    for scheme in ColorScheme::builtin() {
+        let colors: HashSet<Color32> = scheme.colors.into_iter().collect();
+        assert_eq!(colors.len(), 6, "{}", scheme.name);
+        let letters: HashSet<char> = scheme.letters.into_iter().collect();
+        assert_eq!(letters.len(), 6, "{}", scheme.name);
+    }
+    let japanese = ColorScheme::japanese();
+    assert_eq!(
+        japanese.color(Color::Y),
+        ColorScheme::standard().color(Color::B)
+    );
+    assert!(ColorScheme::color_blind().show_letters);
+    assert_eq!(ColorScheme::standard().text_color(Color::W), Color32::BLACK);
+    assert_eq!(ColorScheme::standard().text_color(Color::B), Color32::WHITE);
+}
+
+#[test]
+fn parse_user_schemes() {
+    let text = "\
+# two schemes
+scheme Pastel blue
+W #ffffff
+Y #FDFD96 y
+G #77dd77
+B #779ecb
+R #ff6961
+O #ffb347
+
+scheme Grey
+W #ffffff 1
+Y #dddddd 2
+G #bbbbbb 3
+B #999999 4
+R #777777 5
+O #555555 6
+";
+    let schemes = parse_schemes(text).unwrap();
+    assert_eq!(schemes.len(), 2);
+    assert_eq!(schemes[0].name, "Pastel blue");
+    assert_eq!(
+        schemes[0].color(Color::Y),
+        Color32::from_rgb(0xfd, 0xfd, 0x96)
+    );
+    assert_eq!(schemes[0].letter(Color::Y), 'y');
+    assert_eq!(schemes[0].letter(Color::G), 'G');
+    assert_eq!(schemes[1].letter(Color::O), '6');
+}
+
+#[test]
+fn reject_invalid_schemes() {
+    let missing = "scheme A\nW #ffffff\nY #ffff00\n";
+    assert!(parse_schemes(missing).unwrap_err().contains("all six"));
+    assert!(parse_schemes("W #ffffff").is_err());
+    let bad_color = "scheme A\nW #fffff\n";
+    assert!(parse_schemes(bad_color).unwrap_err().starts_with("line 2"));
+    assert!(parse_schemes("scheme A\nX #ffffff\n").is_err());
+    assert!(parse_schemes("scheme A\nW #ffffff WW\n").is_err());
+    assert_eq!(parse_schemes("# nothing\n"), Ok(Vec::new()));
+}
@@ -0,0 +1,70 @@
+use rubiks::rubiks::color::Color;
+use rubiks::rubiks::cube::RubiksCube;
+use rubiks::rubiks::cubie::CubieCube;
+use rubiks::rubiks::moves::{parse_algorithm, Move};
+use rubiks::rubiks::solver::SearchResult;
+
+// The schema is meant to stay stable, so compare against the exact JSON
+#[test]
+fn schema() {
+    assert_eq!(serde_json::to_string(&Color::G).unwrap(), "\"G\"");
+
+    let solved = serde_json::to_string(&RubiksCube::new()).unwrap();
+    let face = |c: &str| format!("[{}]", vec![format!("\"{}\"", c); 9].join(","));
+    let faces: Vec<String> = ["W", "Y", "G", "B", "R", "O"]
+        .iter()
+        .map(|c| face(c))
+        .collect();
+    assert_eq!(solved, format!("{{\"faces\":[{}]}}", faces.join(",")));
+
+    let algorithm = parse_algorithm("R U' F2").unwrap();
+    assert_eq!(
+        serde_json::to_string(&algorithm).unwrap(),
+        r#"["R","U'","F2"]"#
+    );
+
+    let result = SearchResult {
+        solution: vec!["R".to_string(), "U'".to_string()],
+        nodes_expanded: 42,
+    };
+    assert_eq!(
+        serde_json::to_string(&result).unwrap(),
+        r#"{"solution":["R","U'"],"nodes_expanded":42}"#
+    );
+}
+
+#[test]
+fn round_trips() {
+    let mut cube = RubiksCube::new();
+    cube.apply_scramble("R U2 F' L D B2");
+    let json = serde_json::to_string(&cube).unwrap();
+    assert_eq!(serde_json::from_str::<RubiksCube>(&json).unwrap(), cube);
+
+    let cubie = CubieCube::from_facelets(&cube).unwrap();
+    let json = serde_json::to_string(&cubie).unwrap();
+    assert_eq!(serde_json::from_str::<CubieCube>(&json).unwrap(), cubie);
+
+    // Pieces are checked like stickers are
+    let solved = serde_json::to_string(&CubieCube::new()).unwrap();
+    for (from, to) in [
+        ("\"corner_perm\":[0,1", "\"corner_perm\":[1,1"),
+        ("\"corner_perm\":[0,1", "\"corner_perm\":[9,1"),
+        ("\"corner_orient\":[0,0", "\"corner_orient\":[1,0"),
+        ("\"corner_orient\":[0,0", "\"corner_orient\":[3,0"),
+        ("\"edge_orient\":[0,0", "\"edge_orient\":[1,0"),
+        ("\"edge_perm\":[0,1", "\"edge_perm\":[1,0"),
+    ] {
+        assert!(solved.contains(from));
+        let json = solved.replacen(from, to, 1);
+        assert!(
+            serde_json::from_str::<CubieCube>(&json).is_err(),
+            "{}",
+            json
+        );
+    }
+
+    let moves: Vec<Move> = serde_json::from_str(r#"["B2", "L'", "D"]"#).unwrap();
+    assert_eq!(moves, parse_algorithm("B2 L' D").unwrap());
+    assert!(serde_json::from_str::<Move>(r#""X2""#).is_err());
+    assert!(serde_json::from_str::<Color>(r#""P""#).is_err());
+}
@@ -0,0 +1,124 @@
+use rubiks::rubiks::cube::RubiksCube;
+use rubiks::rubiks::moves::parse_algorithm;
+use rubiks::ui::app::{MyApp, View};
+use rubiks::ui::scheme::ColorScheme;
+use rubiks::ui::session::{Bookmark, Session};
+use std::collections::HashMap;
+
+#[test]
+fn undo_and_redo_moves() {
+    let moves = parse_algorithm("R U F'").unwrap();
+    let mut app = MyApp::new(RubiksCube::new());
+    for &m in &moves {
+        app.apply_move(m);
+    }
+    app.undo();
+    app.undo();
+    let mut expected = RubiksCube::new();
+    expected.apply_move(moves[0]);
+    assert_eq!(app.cube, expected);
+    assert_eq!(app.history, moves[..1]);
+
+    app.redo();
+    expected.apply_move(moves[1]);
+    assert_eq!(app.cube, expected);
+
+    // A new move drops what is left to redo
+    app.apply_move(moves[0]);
+    assert!(app.redo.is_empty());
+    app.redo();
+    assert_eq!(app.history.len(), 3);
+
+    app.undo();
+    app.undo();
+    app.undo();
+    app.undo();
+    assert!(app.cube.is_solved());
+    assert!(app.history.is_empty());
+}
+
+#[test]
+fn session_round_trip() {
+    let mut app = MyApp::new(RubiksCube::new());
+    for m in parse_algorithm("L D2 B'").unwrap() {
+        app.apply_move(m);
+    }
+    app.undo();
+    app.view = View::Perspective;
+    app.camera.yaw = 1.25;
+    app.animation.speed = 7.5;
+    app.solver_settings.max_depth = 9;
+    app.scheme = ColorScheme::color_blind();
+    app.bookmarks.push(Bookmark {
+        name: "after L D2".to_string(),
+        cube: app.cube.clone(),
+    });
+
+    let session = app.session();
+    let text = session.to_string();
+    let restored: Session = text.parse().unwrap();
+    assert_eq!(restored, session);
+    assert_eq!(MyApp::from_session(restored).session(), session);
+}
+
+// Keeps what the app saves in memory
+#[derive(Default)]
+struct MemoryStorage(HashMap<String, String>);
+
+impl eframe::Storage for MemoryStorage {
+    fn get_string(&self, key: &str) -> Option<String> {
+        self.0.get(key).cloned()
+    }
+
+    fn set_string(&mut self, key: &str, value: String) {
+        self.0.insert(key.to_string(), value);
+    }
+
+    fn flush(&mut self) {}
+}
+
+#[test]
+fn a_given_cube_wins_over_the_saved_one() {
+    let mut app = MyApp::new(RubiksCube::new());
+    app.apply_move(parse_algorithm("F").unwrap()[0]);
+    app.animation.speed = 7.5;
+    let mut storage = MemoryStorage::default();
+    eframe::App::save(&mut app, &mut storage);
+
+    let mut scrambled = RubiksCube::new();
+    scrambled.apply_scramble("R U");
+    let restored = MyApp::restore(scrambled.clone(), Some(&storage));
+    assert_eq!(restored.cube, app.cube);
+    assert_eq!(restored.history, app.history);
+
+    let kept = MyApp::restore_with(scrambled.clone(), Some(&storage), true);
+    assert_eq!(kept.cube, scrambled);
+    assert!(kept.history.is_empty());
+    assert_eq!(kept.animation.speed, 7.5);
+}
+
+#[test]
+fn invalid_sessions_are_rejected() {
+    assert!("cube WWW".parse::<Session>().is_err());
+    let text = Session::default()
+        .to_string()
+        .replace("view net", "view top");
+    assert!(text.parse::<Session>().is_err());
+    // Speeds the slider can't be set to
+    for speed in ["0", "-4", "25", "NaN"] {
+        let text = Session::default()
+            .to_string()
+            .replace("animation true 4", &format!("animation true {}", speed));
+        assert!(text.parse::<Session>().is_err(), "{}", speed);
+    }
+    // Nor depths the solver sliders can't be set to
+    for depths in ["0 14", "7 14", "5 0", "5 21"] {
+        let text = Session::default()
+            .to_string()
+            .replace("solver ida 5 14", &format!("solver ida {}", depths));
+        assert!(text.parse::<Session>().is_err(), "{}", depths);
+    }
+    // Keys from newer versions are skipped
+    let text = Session::default().to_string() + "theme dark\n";
+    assert_eq!(text.parse::<Session>(), Ok(Session::default()));
+}
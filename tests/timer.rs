@@ -0,0 +1,102 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rubiks::rubiks::moves::generate_scramble;
+use rubiks::ui::timer::{Penalty, Solve, Timer, TimerSession, TimerState};
+use std::time::{Duration, Instant};
+
+fn solve(millis: u64, penalty: Penalty) -> Solve {
+    Solve {
+        scramble: Vec::new(),
+        time: Duration::from_millis(millis),
+        penalty,
+    }
+}
+
+#[test]
+fn inspection_penalties() {
+    let start = Instant::now();
+    let secs = |s: f64| start + Duration::from_secs_f64(s);
+    for (inspection, penalty) in [
+        (10.0, Penalty::None),
+        (16.0, Penalty::PlusTwo),
+        (18.0, Penalty::Dnf),
+    ] {
+        let mut timer = Timer::default();
+        timer.space_released(start);
+        assert!(matches!(timer.state, TimerState::Inspecting { .. }));
+        assert_eq!(timer.space_pressed(secs(1.0)), None);
+        timer.space_released(secs(inspection));
+        assert_eq!(
+            timer.space_pressed(secs(inspection + 12.5)),
+            Some((Duration::from_secs_f64(12.5), penalty))
+        );
+        assert_eq!(timer.state, TimerState::Stopped);
+        timer.space_released(secs(inspection + 13.0));
+        assert_eq!(timer.state, TimerState::Idle);
+    }
+
+    let mut timer = Timer {
+        inspection: false,
+        ..Timer::default()
+    };
+    timer.space_released(start);
+    assert_eq!(timer.elapsed(secs(3.0)), Some(Duration::from_secs(3)));
+}
+
+#[test]
+fn session_statistics() {
+    let mut session = TimerSession::default();
+    assert_eq!(session.average_of(5), None);
+    for (millis, penalty) in [
+        (10_000, Penalty::None),
+        (12_000, Penalty::None),
+        (9_000, Penalty::PlusTwo),
+        (20_000, Penalty::None),
+        (8_000, Penalty::Dnf),
+    ] {
+        session.solves.push(solve(millis, penalty));
+    }
+    assert_eq!(session.best(), Some(Duration::from_secs(10)));
+    assert_eq!(session.mean(), Some(Duration::from_millis(13_250)));
+    // The DNF and the 10 are dropped
+    assert_eq!(
+        session.average_of(5),
+        Some(Some(Duration::from_secs(43) / 3))
+    );
+    session.solves.push(solve(7_000, Penalty::Dnf));
+    assert_eq!(session.average_of(5), Some(None));
+    assert_eq!(session.average_of(12), None);
+}
+
+#[test]
+fn export_csv() {
+    let mut session = TimerSession::default();
+    session.solves.push(Solve {
+        scramble: generate_scramble(&mut StdRng::seed_from_u64(1), 3),
+        time: Duration::from_millis(11_230),
+        penalty: Penalty::PlusTwo,
+    });
+    let path = std::env::temp_dir().join("rubiks_timer_session.csv");
+    session.write_csv(&path).unwrap();
+    let csv = std::fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "solve,time,penalty,result,scramble");
+    assert!(lines[1].starts_with("1,11.230,+2,13.23,"));
+}
+
+#[test]
+fn scrambles_have_no_redundant_moves() {
+    let mut rng = StdRng::seed_from_u64(7);
+    for _ in 0..100 {
+        let scramble = generate_scramble(&mut rng, 20);
+        assert_eq!(scramble.len(), 20);
+        for pair in scramble.windows(2) {
+            assert_ne!(pair[0].face, pair[1].face);
+        }
+        for triple in scramble.windows(3) {
+            assert!(
+                !(triple[0].face == triple[2].face && triple[1].face == triple[0].face.opposite())
+            );
+        }
+    }
+}
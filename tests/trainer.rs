@@ -0,0 +1,130 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rubiks::rubiks::cases::{is_solved_up_to_auf, Case, CaseSet, OLL_CASES, PLL_CASES};
+use rubiks::rubiks::color::Color;
+use rubiks::rubiks::cube::RubiksCube;
+use rubiks::ui::trainer::{Trainer, TrainerMode};
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+// The U face and the top row of the side faces
+fn is_last_layer(face: usize, index: usize) -> bool {
+    face == 0 || (face >= 2 && index < 3)
+}
+
+fn first_two_layers_solved(cube: &RubiksCube) -> bool {
+    let solved = RubiksCube::new();
+    (0..6).all(|face| {
+        (0..9).all(|i| is_last_layer(face, i) || cube.faces[face][i] == solved.faces[face][i])
+    })
+}
+
+// Stickers of the U colour in the last layer, the same for every side the
+// case is seen from
+fn orientation_pattern(case: &Case) -> String {
+    (0..4)
+        .map(|pre_auf| {
+            let cube = case.setup(pre_auf, 0);
+            (0..6)
+                .flat_map(|face| (0..9).map(move |i| (face, i)))
+                .filter(|&(face, i)| is_last_layer(face, i))
+                .map(|(face, i)| {
+                    if cube.faces[face][i] == Color::W {
+                        'x'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<String>()
+        })
+        .min()
+        .unwrap()
+}
+
+#[test]
+fn cases_only_change_the_last_layer() {
+    for case in OLL_CASES.iter().chain(PLL_CASES.iter()) {
+        let cube = case.setup(1, 3);
+        assert!(first_two_layers_solved(&cube), "{}", case.label());
+        assert!(!is_solved_up_to_auf(&cube), "{}", case.label());
+        let mut cube = case.setup(2, 0);
+        cube.apply_moves(&case.moves());
+        assert!(is_solved_up_to_auf(&cube), "{}", case.label());
+    }
+    for case in &PLL_CASES {
+        let cube = case.setup(0, 0);
+        assert!(
+            cube.faces[0].iter().all(|&c| c == Color::W),
+            "{}",
+            case.label()
+        );
+    }
+}
+
+#[test]
+fn cases_are_distinct() {
+    let patterns: HashSet<String> = OLL_CASES.iter().map(orientation_pattern).collect();
+    assert_eq!(patterns.len(), 57);
+
+    let permutations: HashSet<String> = PLL_CASES
+        .iter()
+        .map(|case| {
+            (0..16)
+                .map(|auf| case.setup(auf / 4, auf % 4).to_string())
+                .min()
+                .unwrap()
+        })
+        .collect();
+    assert_eq!(permutations.len(), 21);
+    assert_eq!(CaseSet::Pll.cases()[15].label(), "T perm");
+}
+
+#[test]
+fn trainer_keeps_stats_per_case() {
+    let mut rng = StdRng::seed_from_u64(3);
+    let start = Instant::now();
+    let mut trainer = Trainer::new();
+    trainer.select_set(CaseSet::Pll, false);
+    assert_eq!(trainer.next_case(&mut rng, start), None);
+    let t_perm = trainer
+        .cases()
+        .iter()
+        .position(|case| case.label() == "T perm")
+        .unwrap();
+    trainer.selected[t_perm] = true;
+
+    trainer.next_case(&mut rng, start).unwrap();
+    let answer = trainer
+        .guess(t_perm, start + Duration::from_secs(2))
+        .unwrap();
+    assert!(answer.correct);
+    trainer.next_case(&mut rng, start).unwrap();
+    assert!(
+        !trainer
+            .guess(0, start + Duration::from_secs(9))
+            .unwrap()
+            .correct
+    );
+    assert_eq!(trainer.guess(t_perm, start), None);
+
+    trainer.mode = TrainerMode::Perform;
+    let mut cube = trainer.next_case(&mut rng, start).unwrap();
+    assert_eq!(trainer.check(&cube, start), None);
+    for turns in 0..4 {
+        let mut solved = cube.clone();
+        solved.apply_scramble(&"U ".repeat(turns));
+        solved.apply_moves(&trainer.cases()[t_perm].moves());
+        if is_solved_up_to_auf(&solved) {
+            cube = solved;
+            break;
+        }
+    }
+    assert!(trainer
+        .check(&cube, start + Duration::from_secs(4))
+        .is_some());
+
+    let stats = trainer.stats(t_perm);
+    assert_eq!((stats.correct, stats.attempts), (2, 3));
+    assert_eq!(stats.mean_time(), Some(Duration::from_secs(3)));
+    assert!((stats.accuracy().unwrap() - 2.0 / 3.0).abs() < 1e-9);
+}
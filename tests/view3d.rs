@@ -0,0 +1,65 @@
+use eframe::egui::{Pos2, Rect, Vec2};
+use rubiks::rubiks::cube::RubiksCube;
+use rubiks::rubiks::moves::{Face, Move, Turn};
+use rubiks::ui::view3d::{
+    drag_turn, face_normal, in_layer, rotate, sticker_at, sticker_center, sticker_under,
+    turn_rotation, Camera,
+};
+
+// Rotating the stickers of the turned layer in 3D must give the same
+// state as the facelet permutation of the move
+#[test]
+fn geometry_matches_moves() {
+    let mut cube = RubiksCube::new();
+    cube.apply_scramble("R U' F2 D L B'");
+    for m in Move::all() {
+        let mut turned = cube.clone();
+        turned.apply_move(m);
+        let (axis, angle) = turn_rotation(m);
+        for face in 0..6 {
+            for index in 0..9 {
+                let center = sticker_center(face, index);
+                let (to_face, to_index) = if in_layer(m.face, center) {
+                    sticker_at(
+                        rotate(center, axis, angle),
+                        rotate(face_normal(face), axis, angle),
+                    )
+                    .unwrap()
+                } else {
+                    (face, index)
+                };
+                assert_eq!(
+                    turned.faces[to_face][to_index], cube.faces[face][index],
+                    "{} moves sticker {} of face {}",
+                    m, index, face
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn default_camera_shows_up_front_and_right() {
+    let camera = Camera::default();
+    let visible: Vec<usize> = (0..6).filter(|&face| camera.is_visible(face)).collect();
+    assert_eq!(
+        visible,
+        vec![Face::U.index(), Face::F.index(), Face::R.index()]
+    );
+}
+
+#[test]
+fn picking_and_drag_turns() {
+    let camera = Camera::default();
+    let rect = Rect::from_min_size(Pos2::ZERO, Vec2::splat(600.0));
+    for face in [Face::U, Face::F, Face::R] {
+        let (center, _) = camera.project(sticker_center(face.index(), 4), rect);
+        assert_eq!(sticker_under(&camera, rect, center), Some((face, 4)));
+    }
+
+    // Dragging the top of the front face to the left turns it counterclockwise
+    let (top, _) = camera.project(sticker_center(Face::F.index(), 1), rect);
+    let m = drag_turn(&camera, rect, Face::F, top, top - Vec2::new(40.0, 0.0));
+    assert_eq!(m, Some(Move::new(Face::F, Turn::CounterClockwise)));
+    assert_eq!(drag_turn(&camera, rect, Face::F, top, top), None);
+}